@@ -2,6 +2,23 @@ use serde_json::{Value, Map, Number};
 use sha2::{Sha256, Digest};
 use std::collections::BTreeMap;
 
+/// Integer constraint filled in by canonicalize_constraints when absent
+pub struct IntegerConstraint {
+    pub field: &'static str,
+    pub default: u64,
+    pub max: u64,
+}
+
+/// Schema version the canonicalizer accepts
+pub const SCHEMA_VERSION: &str = "1.0.0";
+
+/// Integer constraint defaults and upper bounds for SCHEMA_VERSION
+pub const INTEGER_CONSTRAINTS: [IntegerConstraint; 3] = [
+    IntegerConstraint { field: "max_fee_bps", default: 30, max: 100 },
+    IntegerConstraint { field: "max_funding_bps_8h", default: 50, max: 100 },
+    IntegerConstraint { field: "max_slippage_bps", default: 100, max: 1000 },
+];
+
 /// Deep canonicalization rules for DeltaNEAR Derivatives v1.0.0
 /// 
 /// IMMUTABLE SPECIFICATION - ANY CHANGE BREAKS COMPATIBILITY
@@ -27,7 +44,7 @@ impl Canonicalizer {
             .and_then(|v| v.as_str())
             .ok_or("Missing or invalid version")?;
         
-        if version != "1.0.0" {
+        if version != SCHEMA_VERSION {
             return Err(format!("Invalid version: {}. Must be 1.0.0", version));
         }
 
@@ -87,14 +104,14 @@ impl Canonicalizer {
         keys.sort();
         
         // Check for required fields and no extras
-        let required = vec!["collateral", "instrument", "side", "size", "symbol"];
+        let required = ["collateral", "instrument", "side", "size", "symbol"];
         for field in &required {
             if !keys.contains(field) {
                 return Err(format!("Missing required field: {}", field));
             }
         }
         
-        let allowed = vec!["collateral", "constraints", "instrument", "leverage", "option", "side", "size", "symbol"];
+        let allowed = ["collateral", "constraints", "instrument", "leverage", "option", "side", "size", "symbol"];
         for key in &keys {
             if !allowed.contains(key) {
                 return Err(format!("Unknown field in derivatives: {}", key));
@@ -295,38 +312,18 @@ impl Canonicalizer {
             }
         }
 
-        // max_fee_bps (integer, default 30)
-        let max_fee_bps = constraints
-            .and_then(|c| c.get("max_fee_bps"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(30);
-        if max_fee_bps > 100 {
-            return Err(format!("max_fee_bps {} exceeds 100", max_fee_bps));
-        }
-        canonical.insert("max_fee_bps".to_string(), 
-            Value::Number(Number::from(max_fee_bps)));
-
-        // max_funding_bps_8h (integer, default 50)
-        let max_funding_bps_8h = constraints
-            .and_then(|c| c.get("max_funding_bps_8h"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(50);
-        if max_funding_bps_8h > 100 {
-            return Err(format!("max_funding_bps_8h {} exceeds 100", max_funding_bps_8h));
-        }
-        canonical.insert("max_funding_bps_8h".to_string(), 
-            Value::Number(Number::from(max_funding_bps_8h)));
-
-        // max_slippage_bps (integer, default 100)
-        let max_slippage_bps = constraints
-            .and_then(|c| c.get("max_slippage_bps"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(100);
-        if max_slippage_bps > 1000 {
-            return Err(format!("max_slippage_bps {} exceeds 1000", max_slippage_bps));
+        // max_fee_bps, max_funding_bps_8h, max_slippage_bps (integers with defaults)
+        for constraint in &INTEGER_CONSTRAINTS {
+            let value = constraints
+                .and_then(|c| c.get(constraint.field))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(constraint.default);
+            if value > constraint.max {
+                return Err(format!("{} {} exceeds {}", constraint.field, value, constraint.max));
+            }
+            canonical.insert(constraint.field.to_string(), 
+                Value::Number(Number::from(value)));
         }
-        canonical.insert("max_slippage_bps".to_string(), 
-            Value::Number(Number::from(max_slippage_bps)));
 
         // venue_allowlist (sorted, deduplicated, lowercase)
         let mut venue_allowlist: Vec<String> = constraints
@@ -385,7 +382,7 @@ impl Canonicalizer {
     }
 
     /// Normalize ISO 8601 timestamp to seconds precision
-    fn normalize_timestamp(ts: &str) -> Result<String, String> {
+    pub fn normalize_timestamp(ts: &str) -> Result<String, String> {
         let trimmed = ts.trim();
         
         // Must end with Z
@@ -424,7 +421,7 @@ impl Canonicalizer {
     }
 
     /// Normalize signer_id (NEAR account rules)
-    fn normalize_signer_id(signer_id: &str) -> Result<String, String> {
+    pub fn normalize_signer_id(signer_id: &str) -> Result<String, String> {
         let normalized = signer_id.trim().to_lowercase();
         
         // Basic NEAR account validation
@@ -453,13 +450,121 @@ impl Canonicalizer {
         // Return full 64-character hex digest (256 bits = 32 bytes = 64 hex chars)
         format!("{:x}", result)
     }
+
+    /// hash_version 2: SHA-256 over "deltanear:intent:<schema_version>\n" followed
+    /// by the canonical JSON, so intents of different schema versions can never
+    /// share a hash even if they canonicalize to identical bytes
+    pub fn compute_domain_hash(canonical_json: &str, schema_version: &str) -> String {
+        Self::compute_hash(&format!("deltanear:intent:{}\n{}", schema_version, canonical_json))
+    }
+
+    /// hash_version 3: like compute_domain_hash, but over the RFC 8785 output of
+    /// to_jcs_string and under its own domain tag
+    pub fn compute_jcs_hash(jcs: &str, schema_version: &str) -> String {
+        Self::compute_hash(&format!("deltanear:intent:{}:jcs\n{}", schema_version, jcs))
+    }
+
+    /// Serialize per RFC 8785 (JCS) without relying on serde_json's formatter:
+    /// object keys sorted by UTF-16 code units, minimal string escaping and
+    /// ECMAScript number formatting
+    pub fn to_jcs_string(value: &Value) -> Result<String, String> {
+        let mut out = String::new();
+        Self::write_jcs(value, &mut out)?;
+        Ok(out)
+    }
+
+    fn write_jcs(value: &Value, out: &mut String) -> Result<(), String> {
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) => out.push_str(&Self::jcs_number(n)?),
+            Value::String(s) => Self::write_jcs_string(s, out),
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    Self::write_jcs(item, out)?;
+                }
+                out.push(']');
+            }
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+                out.push('{');
+                for (i, (key, item)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    Self::write_jcs_string(key, out);
+                    out.push(':');
+                    Self::write_jcs(item, out)?;
+                }
+                out.push('}');
+            }
+        }
+        Ok(())
+    }
+
+    /// RFC 8785 section 3.2.2.2: only '"', '\\' and C0 controls are escaped,
+    /// using the short forms where JSON defines them
+    fn write_jcs_string(s: &str, out: &mut String) {
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\u{8}' => out.push_str("\\b"),
+                '\u{c}' => out.push_str("\\f"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    /// ECMAScript Number::toString, which RFC 8785 mandates for numbers
+    fn jcs_number(n: &Number) -> Result<String, String> {
+        const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+        if let Some(u) = n.as_u64() {
+            if u > MAX_SAFE_INTEGER {
+                return Err(format!("Integer {} is not exactly representable in JCS", u));
+            }
+            return Ok(u.to_string());
+        }
+        if let Some(i) = n.as_i64() {
+            if i.unsigned_abs() > MAX_SAFE_INTEGER {
+                return Err(format!("Integer {} is not exactly representable in JCS", i));
+            }
+            return Ok(i.to_string());
+        }
+        let f = n.as_f64().ok_or("Number is not representable as f64")?;
+        if !f.is_finite() {
+            return Err(format!("Non-finite number not allowed: {}", f));
+        }
+        if f == 0.0 {
+            return Ok("0".to_string());
+        }
+        if (1e-6..1e21).contains(&f.abs()) {
+            // Rust's Display is the shortest round-trip digits without exponent
+            return Ok(format!("{}", f));
+        }
+        let exp = format!("{:e}", f);
+        Ok(match exp.split_once('e') {
+            Some((mantissa, e)) if !e.starts_with('-') => format!("{}e+{}", mantissa, e),
+            _ => exp,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
-    use proptest::prelude::*;
 
     #[test]
     fn test_strict_field_validation() {
@@ -601,5 +706,43 @@ mod tests {
         assert_eq!(canonical["signer_id"], "alice.near");
         assert_eq!(canonical["deadline"], "2024-01-23T11:00:00Z");
         assert_eq!(canonical["nonce"], "12345");
+
+        // JCS output must agree with the serde_json bytes hashed by versions 1 and 2
+        let jcs = Canonicalizer::to_jcs_string(&canonical).unwrap();
+        assert_eq!(jcs, serde_json::to_string(&canonical).unwrap());
+    }
+
+    #[test]
+    fn test_jcs_string_escaping() {
+        let value = json!({ "s": "q\"b\\\u{8}\u{c}\n\r\t\u{1}\u{7f}é😀" });
+        assert_eq!(
+            Canonicalizer::to_jcs_string(&value).unwrap(),
+            "{\"s\":\"q\\\"b\\\\\\b\\f\\n\\r\\t\\u0001\u{7f}é😀\"}"
+        );
+    }
+
+    #[test]
+    fn test_jcs_key_order_uses_utf16_units() {
+        // U+FB01 sorts before U+1F600 in UTF-8 but after it in UTF-16
+        let value = json!({ "ﬁ": 2, "😀": 1, "a": 3 });
+        assert_eq!(
+            Canonicalizer::to_jcs_string(&value).unwrap(),
+            "{\"a\":3,\"😀\":1,\"ﬁ\":2}"
+        );
+    }
+
+    #[test]
+    fn test_jcs_number_formatting() {
+        let cases = [
+            (json!(100), "100"),
+            (json!(1.5), "1.5"),
+            (json!(0.000001), "0.000001"),
+            (json!(1.5e-7), "1.5e-7"),
+            (json!(1e21), "1e+21"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(Canonicalizer::to_jcs_string(&value).unwrap(), expected);
+        }
+        assert!(Canonicalizer::to_jcs_string(&json!(9007199254740993u64)).is_err());
     }
 }
\ No newline at end of file
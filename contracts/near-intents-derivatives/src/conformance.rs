@@ -0,0 +1,241 @@
+/// Seeded generator of adversarial intent JSON for SDK conformance testing
+///
+/// Every case is derived from (seed, index) alone, so case N is identical no
+/// matter how many cases are requested. The generator only produces raw
+/// input text; the expected canonical form and hash come from Canonicalizer,
+/// including rejections, which SDKs must reproduce as well.
+pub struct CorpusGenerator;
+
+pub struct GeneratedIntent {
+    pub case_id: String,
+    pub mutations: Vec<String>,
+    pub input_json: String,
+}
+
+/// Sizes at and around the canonical decimal bounds, some deliberately invalid
+const EDGE_SIZES: [&str; 10] = [
+    "0.00000001",
+    "1000000",
+    "1000000.00000000",
+    "123.45600000",
+    "0.1",
+    "1e3",
+    "00.5",
+    "+1",
+    "0.000000001",
+    "1000000.01",
+];
+
+/// splitmix64: tiny, portable and easy to reimplement in other SDKs
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+struct CaseBuilder {
+    rng: SplitMix64,
+    mutations: Vec<String>,
+    whitespace: bool,
+    unicode_escapes: bool,
+}
+
+impl CaseBuilder {
+    fn gap(&mut self) -> &'static str {
+        if !self.whitespace {
+            return "";
+        }
+        ["", " ", "\n", "\t", "  \r\n "][self.rng.below(5)]
+    }
+
+    fn string(&mut self, value: &str) -> String {
+        let mut out = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c if self.unicode_escapes && self.rng.chance() => {
+                    let mut units = [0u16; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        out.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    fn object(&mut self, mut fields: Vec<(&str, String)>, permute: bool) -> String {
+        if permute {
+            self.rng.shuffle(&mut fields);
+        }
+        let mut out = String::from("{");
+        for (i, (key, value)) in fields.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let key = self.string(key);
+            let (a, b, c) = (self.gap(), self.gap(), self.gap());
+            out.push_str(&format!("{}{}{}:{}{}", a, key, b, c, value));
+        }
+        out.push_str(self.gap());
+        out.push('}');
+        out
+    }
+
+    fn mutate(&mut self, name: &str) -> bool {
+        let hit = self.rng.chance();
+        if hit {
+            self.mutations.push(name.to_string());
+        }
+        hit
+    }
+}
+
+impl CorpusGenerator {
+    pub fn generate(seed: u64, count: usize) -> Vec<GeneratedIntent> {
+        (0..count as u64).map(|index| Self::case(seed, index)).collect()
+    }
+
+    fn case(seed: u64, index: u64) -> GeneratedIntent {
+        let mut rng = SplitMix64(seed ^ index.wrapping_mul(0xD134_2543_DE82_EF95));
+        rng.next();
+        let mut b = CaseBuilder {
+            rng,
+            mutations: Vec::new(),
+            whitespace: false,
+            unicode_escapes: false,
+        };
+        b.whitespace = b.mutate("whitespace");
+        b.unicode_escapes = b.mutate("unicode_escape");
+        let permute = b.mutate("field_order");
+        let mixed_case = b.mutate("mixed_case");
+        let padding = b.mutate("padding");
+        let edge_size = b.mutate("edge_decimal");
+        let numeric = b.mutate("numeric_literals");
+        let millis = b.mutate("millisecond_deadline");
+        let option = b.mutate("option_instrument");
+        let unicode_token = b.mutate("non_ascii_token");
+
+        let pad = |s: &str| if padding { format!(" {}\t", s) } else { s.to_string() };
+        let (symbol, side, chain) = if mixed_case {
+            ("bTc-uSd", "LoNg", "NEAR")
+        } else {
+            ("BTC-USD", "long", "near")
+        };
+        let size = if edge_size {
+            EDGE_SIZES[b.rng.below(EDGE_SIZES.len())]
+        } else {
+            "1.5"
+        };
+        let size = if numeric && !size.starts_with(['+', '0']) {
+            size.to_string()
+        } else {
+            b.string(&pad(size))
+        };
+        let token = if unicode_token { "usdc.ñear" } else { "usdc.near" };
+        let deadline = if millis {
+            "2024-12-31T23:59:59.999Z"
+        } else {
+            "2024-12-31T23:59:59Z"
+        };
+        let nonce = if numeric {
+            (b.rng.next() % 1_000_000_000).to_string()
+        } else {
+            b.string(&format!("n-{}", index))
+        };
+
+        let collateral_fields = vec![
+            ("token", b.string(&pad(token))),
+            ("chain", b.string(&pad(chain))),
+        ];
+        let collateral = b.object(collateral_fields, permute);
+        let mut derivative_fields = vec![
+            ("collateral", collateral),
+            ("side", b.string(&pad(side))),
+            ("size", size),
+            ("symbol", b.string(&pad(symbol))),
+        ];
+        if option {
+            let option_fields = vec![
+                ("kind", b.string(&pad("Call"))),
+                ("strike", b.string("50000.00")),
+                ("expiry", b.string("2024-12-31T00:00:00.000Z")),
+            ];
+            let option_params = b.object(option_fields, permute);
+            derivative_fields.push(("instrument", b.string(&pad("option"))));
+            derivative_fields.push(("option", option_params));
+        } else {
+            derivative_fields.push(("instrument", b.string(&pad("perp"))));
+        }
+        let derivatives = b.object(derivative_fields, permute);
+        let root_fields = vec![
+            ("version", b.string("1.0.0")),
+            ("intent_type", b.string("derivatives")),
+            ("derivatives", derivatives),
+            ("signer_id", b.string(&pad(if mixed_case { "Alice.NEAR" } else { "alice.near" }))),
+            ("deadline", b.string(deadline)),
+            ("nonce", nonce),
+        ];
+        let mut input_json = b.gap().to_string();
+        input_json.push_str(&b.object(root_fields, permute));
+        input_json.push_str(b.gap());
+
+        GeneratedIntent {
+            case_id: format!("{}-{}", seed, index),
+            mutations: b.mutations,
+            input_json,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_deterministic_per_index() {
+        let short = CorpusGenerator::generate(7, 3);
+        let long = CorpusGenerator::generate(7, 10);
+        for (a, b) in short.iter().zip(long.iter()) {
+            assert_eq!(a.case_id, b.case_id);
+            assert_eq!(a.input_json, b.input_json);
+        }
+        let other = CorpusGenerator::generate(8, 10);
+        assert!(long
+            .iter()
+            .zip(other.iter())
+            .any(|(a, b)| a.input_json != b.input_json));
+    }
+
+    #[test]
+    fn test_corpus_inputs_are_valid_json() {
+        for case in CorpusGenerator::generate(42, 50) {
+            let parsed: serde_json::Value = serde_json::from_str(&case.input_json).unwrap();
+            assert!(parsed.is_object(), "case {}", case.case_id);
+        }
+    }
+}
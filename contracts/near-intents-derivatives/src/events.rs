@@ -1,6 +1,8 @@
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde::Serialize;
 use near_sdk::serde_json::{self, json};
 use near_sdk::{log, AccountId};
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
 
 /// NEP-297 Event Standard Implementation for DeltaNEAR Derivatives v1.0.0
 /// 
@@ -8,6 +10,12 @@ use near_sdk::{log, AccountId};
 /// 
 /// Event format MUST be:
 /// EVENT_JSON:{"standard":"deltanear_derivatives","version":"1.0.0","event":"<event_name>","data":[{...}]}
+///
+/// Every data object carries "topic" next to the event's own fields:
+/// "<symbol>:<instrument>:<event_name>" for the intent the event is about, e.g.
+/// "BTC-USD:perp:execution_logged". Symbol and instrument come from the stored
+/// intent metadata and are "unknown" when none was stored. "trace_id" is added
+/// the same way, only when the client supplied one at metadata store.
 
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -23,10 +31,11 @@ pub struct Nep297Event {
 }
 
 /// Event data for intent_submitted
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct IntentSubmittedData {
     pub intent_hash: String,
+    #[schemars(with = "String")]
     pub signer_id: AccountId,
     pub instrument: String,
     pub symbol: String,
@@ -37,10 +46,11 @@ pub struct IntentSubmittedData {
 }
 
 /// Event data for execution_logged
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ExecutionLoggedData {
     pub intent_hash: String,
+    #[schemars(with = "String")]
     pub solver_id: AccountId,
     pub venue: String,
     pub fill_price: String,
@@ -52,17 +62,18 @@ pub struct ExecutionLoggedData {
 }
 
 /// Event data for solver_assigned
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct SolverAssignedData {
     pub intent_hash: String,
+    #[schemars(with = "String")]
     pub solver_id: AccountId,
     /// Timestamp in nanoseconds since Unix epoch
     pub timestamp_ns: u64,
 }
 
 /// Event data for simulation_completed
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct SimulationCompletedData {
     pub intent_hash: String,
@@ -74,7 +85,7 @@ pub struct SimulationCompletedData {
 }
 
 /// Event data for settlement_initiated
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct SettlementInitiatedData {
     pub intent_hash: String,
@@ -85,7 +96,7 @@ pub struct SettlementInitiatedData {
 }
 
 /// Event data for settlement_completed
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct SettlementCompletedData {
     pub intent_hash: String,
@@ -94,6 +105,131 @@ pub struct SettlementCompletedData {
     pub timestamp_ns: u64,
 }
 
+/// Event data for log_correction_proposed
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogCorrectionProposedData {
+    pub intent_hash: String,
+    pub correction_id: u32,
+    #[schemars(with = "String")]
+    pub solver_id: AccountId,
+    pub evidence_uri: String,
+    /// Correction being replaced; null means the original execution log
+    pub supersedes: Option<u32>,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for log_correction_approved and log_correction_rejected
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogCorrectionReviewedData {
+    pub intent_hash: String,
+    pub correction_id: u32,
+    #[schemars(with = "String")]
+    pub reviewer_id: AccountId,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for intent_cancelled
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentCancelledData {
+    pub intent_hash: String,
+    #[schemars(with = "String")]
+    pub cancelled_by: AccountId,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for intent_expired
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentExpiredData {
+    pub intent_hash: String,
+    /// Intent deadline in nanoseconds since Unix epoch
+    pub deadline_ns: u64,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for intent_amended
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentAmendedData {
+    /// Replaced intent, now cancelled
+    pub old_hash: String,
+    pub new_hash: String,
+    #[schemars(with = "String")]
+    pub signer_id: AccountId,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for document_anchored
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DocumentAnchoredData {
+    pub intent_hash: String,
+    /// Lowercase hex sha256 of the off-chain document
+    pub doc_hash: String,
+    pub doc_type: String,
+    #[schemars(with = "String")]
+    pub anchored_by: AccountId,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for bracket_resolved
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BracketResolvedData {
+    pub parent_hash: String,
+    /// Child whose fill resolved the bracket
+    pub filled_hash: String,
+    /// Siblings cancelled by the fill
+    pub cancelled: Vec<String>,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Event data for admin_action
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AdminActionData {
+    pub action_id: u64,
+    /// Treasury method that was called
+    pub action: String,
+    pub target: String,
+    #[schemars(with = "String")]
+    pub actor: AccountId,
+    pub reason: String,
+    /// Timestamp in nanoseconds since Unix epoch
+    pub timestamp_ns: u64,
+}
+
+/// Symbol and instrument of the intent an event is about, used for topics,
+/// plus the intent's trace_id if it has one
+pub struct EventMarket {
+    pub symbol: String,
+    pub instrument: String,
+    pub trace_id: Option<String>,
+}
+
+/// A data object as emitted: the event's own fields plus the topic and,
+/// when the intent has one, its trace_id
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(rename = "{T}")]
+pub struct EmittedData<T> {
+    #[serde(flatten)]
+    pub fields: T,
+    pub topic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
 pub struct EventEmitter;
 
 impl EventEmitter {
@@ -104,22 +240,21 @@ impl EventEmitter {
     pub fn emit_intent_submitted(
         intent_hash: String,
         signer_id: AccountId,
-        instrument: String,
-        symbol: String,
         side: String,
         size: String,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = IntentSubmittedData {
             intent_hash,
             signer_id,
-            instrument,
-            symbol,
+            instrument: market.instrument.clone(),
+            symbol: market.symbol.clone(),
             side,
             size,
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("intent_submitted", vec![json!(data)]);
+        Self::emit_event("intent_submitted", market, vec![json!(data)])
     }
 
     /// Emit execution_logged event
@@ -130,7 +265,8 @@ impl EventEmitter {
         fill_price: String,
         notional: String,
         status: String,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = ExecutionLoggedData {
             intent_hash,
             solver_id,
@@ -141,21 +277,22 @@ impl EventEmitter {
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("execution_logged", vec![json!(data)]);
+        Self::emit_event("execution_logged", market, vec![json!(data)])
     }
 
     /// Emit solver_assigned event
     pub fn emit_solver_assigned(
         intent_hash: String,
         solver_id: AccountId,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = SolverAssignedData {
             intent_hash,
             solver_id,
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("solver_assigned", vec![json!(data)]);
+        Self::emit_event("solver_assigned", market, vec![json!(data)])
     }
 
     /// Emit simulation_completed event
@@ -164,7 +301,8 @@ impl EventEmitter {
         simulation_hash: String,
         success: bool,
         error_message: Option<String>,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = SimulationCompletedData {
             intent_hash,
             simulation_hash,
@@ -173,39 +311,235 @@ impl EventEmitter {
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("simulation_completed", vec![json!(data)]);
+        Self::emit_event("simulation_completed", market, vec![json!(data)])
     }
 
     /// Emit settlement_initiated event
     pub fn emit_settlement_initiated(
         intent_hash: String,
         token_diff: serde_json::Value,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = SettlementInitiatedData {
             intent_hash,
             token_diff,
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("settlement_initiated", vec![json!(data)]);
+        Self::emit_event("settlement_initiated", market, vec![json!(data)])
     }
 
     /// Emit settlement_completed event
     pub fn emit_settlement_completed(
         intent_hash: String,
         tx_hash: String,
-    ) {
+        market: &EventMarket,
+    ) -> String {
         let data = SettlementCompletedData {
             intent_hash,
             tx_hash,
             timestamp_ns: near_sdk::env::block_timestamp(),
         };
 
-        Self::emit_event("settlement_completed", vec![json!(data)]);
+        Self::emit_event("settlement_completed", market, vec![json!(data)])
+    }
+
+    /// Emit log_correction_proposed event
+    pub fn emit_log_correction_proposed(
+        intent_hash: String,
+        correction_id: u32,
+        solver_id: AccountId,
+        evidence_uri: String,
+        supersedes: Option<u32>,
+        market: &EventMarket,
+    ) -> String {
+        let data = LogCorrectionProposedData {
+            intent_hash,
+            correction_id,
+            solver_id,
+            evidence_uri,
+            supersedes,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("log_correction_proposed", market, vec![json!(data)])
+    }
+
+    /// Emit log_correction_approved or log_correction_rejected event
+    pub fn emit_log_correction_reviewed(
+        intent_hash: String,
+        correction_id: u32,
+        reviewer_id: AccountId,
+        approved: bool,
+        market: &EventMarket,
+    ) -> String {
+        let data = LogCorrectionReviewedData {
+            intent_hash,
+            correction_id,
+            reviewer_id,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        let event_name = if approved {
+            "log_correction_approved"
+        } else {
+            "log_correction_rejected"
+        };
+        Self::emit_event(event_name, market, vec![json!(data)])
+    }
+
+    /// Emit intent_cancelled event
+    pub fn emit_intent_cancelled(
+        intent_hash: String,
+        cancelled_by: AccountId,
+        market: &EventMarket,
+    ) -> String {
+        let data = IntentCancelledData {
+            intent_hash,
+            cancelled_by,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("intent_cancelled", market, vec![json!(data)])
+    }
+
+    /// Emit intent_expired event
+    pub fn emit_intent_expired(
+        intent_hash: String,
+        deadline_ns: u64,
+        market: &EventMarket,
+    ) -> String {
+        let data = IntentExpiredData {
+            intent_hash,
+            deadline_ns,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("intent_expired", market, vec![json!(data)])
     }
 
-    /// Internal method to emit events in exact NEP-297 format
-    fn emit_event(event_name: &'static str, data: Vec<serde_json::Value>) {
+    /// Emit intent_amended event on the market of the new intent
+    pub fn emit_intent_amended(
+        old_hash: String,
+        new_hash: String,
+        signer_id: AccountId,
+        market: &EventMarket,
+    ) -> String {
+        let data = IntentAmendedData {
+            old_hash,
+            new_hash,
+            signer_id,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("intent_amended", market, vec![json!(data)])
+    }
+
+    /// Emit document_anchored event
+    pub fn emit_document_anchored(
+        intent_hash: String,
+        doc_hash: String,
+        doc_type: String,
+        anchored_by: AccountId,
+        market: &EventMarket,
+    ) -> String {
+        let data = DocumentAnchoredData {
+            intent_hash,
+            doc_hash,
+            doc_type,
+            anchored_by,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("document_anchored", market, vec![json!(data)])
+    }
+
+    /// Emit bracket_resolved event on the market of the filled child
+    pub fn emit_bracket_resolved(
+        parent_hash: String,
+        filled_hash: String,
+        cancelled: Vec<String>,
+        market: &EventMarket,
+    ) -> String {
+        let data = BracketResolvedData {
+            parent_hash,
+            filled_hash,
+            cancelled,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+
+        Self::emit_event("bracket_resolved", market, vec![json!(data)])
+    }
+
+    /// Emit admin_action event; it concerns no intent, so its topic is
+    /// "unknown:unknown:admin_action"
+    pub fn emit_admin_action(
+        action_id: u64,
+        action: &str,
+        target: String,
+        actor: AccountId,
+        reason: String,
+    ) -> String {
+        let data = AdminActionData {
+            action_id,
+            action: action.to_string(),
+            target,
+            actor,
+            reason,
+            timestamp_ns: near_sdk::env::block_timestamp(),
+        };
+        let market = EventMarket {
+            symbol: "unknown".to_string(),
+            instrument: "unknown".to_string(),
+            trace_id: None,
+        };
+
+        Self::emit_event("admin_action", &market, vec![json!(data)])
+    }
+
+    /// JSON Schema of each event's data payload as emitted, keyed by event name
+    pub fn event_schemas() -> Vec<(&'static str, RootSchema)> {
+        vec![
+            ("intent_submitted", schema_for!(EmittedData<IntentSubmittedData>)),
+            ("execution_logged", schema_for!(EmittedData<ExecutionLoggedData>)),
+            ("solver_assigned", schema_for!(EmittedData<SolverAssignedData>)),
+            ("simulation_completed", schema_for!(EmittedData<SimulationCompletedData>)),
+            ("settlement_initiated", schema_for!(EmittedData<SettlementInitiatedData>)),
+            ("settlement_completed", schema_for!(EmittedData<SettlementCompletedData>)),
+            ("log_correction_proposed", schema_for!(EmittedData<LogCorrectionProposedData>)),
+            ("log_correction_approved", schema_for!(EmittedData<LogCorrectionReviewedData>)),
+            ("log_correction_rejected", schema_for!(EmittedData<LogCorrectionReviewedData>)),
+            ("intent_cancelled", schema_for!(EmittedData<IntentCancelledData>)),
+            ("intent_expired", schema_for!(EmittedData<IntentExpiredData>)),
+            ("intent_amended", schema_for!(EmittedData<IntentAmendedData>)),
+            ("document_anchored", schema_for!(EmittedData<DocumentAnchoredData>)),
+            ("bracket_resolved", schema_for!(EmittedData<BracketResolvedData>)),
+            ("admin_action", schema_for!(EmittedData<AdminActionData>)),
+        ]
+    }
+
+    /// Topic for an event about an intent on the given market
+    pub fn topic(market: &EventMarket, event_name: &str) -> String {
+        format!("{}:{}:{}", market.symbol, market.instrument, event_name)
+    }
+
+    /// Internal method to emit events in exact NEP-297 format; returns the topic
+    fn emit_event(
+        event_name: &'static str,
+        market: &EventMarket,
+        data: Vec<serde_json::Value>,
+    ) -> String {
+        let topic = Self::topic(market, event_name);
+        let data = data
+            .into_iter()
+            .map(|fields| {
+                json!(EmittedData {
+                    fields,
+                    topic: topic.clone(),
+                    trace_id: market.trace_id.clone(),
+                })
+            })
+            .collect();
         let event = Nep297Event {
             standard: Self::STANDARD,
             version: Self::VERSION,
@@ -218,6 +552,7 @@ impl EventEmitter {
 
         // Emit in exact NEP-297 format
         log!("EVENT_JSON:{}", event_json);
+        topic
     }
 }
 
@@ -243,6 +578,31 @@ mod tests {
         assert_eq!(parsed["event"], "test_event");
         assert!(parsed["data"].is_array());
         assert_eq!(parsed["data"].as_array().unwrap().len(), 1);
+        // The envelope holds exactly the 1.0.0 fields
+        assert_eq!(parsed.as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_topic_and_trace_id_in_data() {
+        near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new().build());
+        let market = EventMarket {
+            symbol: "BTC-USD".to_string(),
+            instrument: "perp".to_string(),
+            trace_id: Some("trace-1".to_string()),
+        };
+        let topic = EventEmitter::emit_intent_cancelled(
+            "h1".to_string(),
+            "alice.near".parse().unwrap(),
+            &market,
+        );
+        assert_eq!(topic, "BTC-USD:perp:intent_cancelled");
+
+        let logs = near_sdk::test_utils::get_logs();
+        let event: serde_json::Value =
+            serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert!(event.get("topic").is_none() && event.get("trace_id").is_none());
+        assert_eq!(event["data"][0]["topic"], "BTC-USD:perp:intent_cancelled");
+        assert_eq!(event["data"][0]["trace_id"], "trace-1");
     }
 
     #[test]
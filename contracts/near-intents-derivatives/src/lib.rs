@@ -1,8 +1,155 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{log, near, AccountId, PanicOnDefault};
+use near_sdk::serde_json;
+use near_sdk::store::{IterableSet, LookupMap, LookupSet, Vector};
+use near_sdk::{
+    env, log, near, require, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise,
+};
 use schemars::JsonSchema;
 
+pub mod canonicalization;
+pub mod conformance;
+pub mod events;
+
+use canonicalization::{Canonicalizer, INTEGER_CONSTRAINTS, SCHEMA_VERSION};
+use conformance::CorpusGenerator;
+use events::{EventEmitter, EventMarket};
+
+/// Window during which a reviewer may act on a proposed log correction
+const CORRECTION_REVIEW_WINDOW_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// How long a solver's idempotency key is remembered
+const IDEMPOTENCY_KEY_TTL_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Drafts that are not approved and stored within this window go stale
+const DRAFT_EXPIRY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Fixed-point precision used when aggregating notional strings
+const NOTIONAL_DECIMALS: u32 = 6;
+/// Fixed-point precision used when comparing trigger prices
+const PRICE_DECIMALS: u32 = 8;
+/// Decimal places the canonicalizer accepts for an intent size
+const SIZE_DECIMALS: u32 = 8;
+
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+
+struct ParameterSpec {
+    key: &'static str,
+    unit: &'static str,
+    min: u64,
+    max: u64,
+    default: u64,
+}
+
+/// Governance-tunable values with their bounds; defaults are the constants above
+const PARAMETERS: [ParameterSpec; 4] = [
+    ParameterSpec {
+        key: "correction_review_window_ns",
+        unit: "ns",
+        min: HOUR_NS,
+        max: 7 * DAY_NS,
+        default: CORRECTION_REVIEW_WINDOW_NS,
+    },
+    ParameterSpec {
+        key: "idempotency_key_ttl_ns",
+        unit: "ns",
+        min: HOUR_NS,
+        max: 7 * DAY_NS,
+        default: IDEMPOTENCY_KEY_TTL_NS,
+    },
+    ParameterSpec {
+        key: "draft_expiry_ns",
+        unit: "ns",
+        min: HOUR_NS,
+        max: 7 * DAY_NS,
+        default: DRAFT_EXPIRY_NS,
+    },
+    ParameterSpec {
+        key: "max_execution_attempts",
+        unit: "attempts",
+        min: 1,
+        max: 10,
+        default: MAX_EXECUTION_ATTEMPTS,
+    },
+];
+
+/// Daily volume buckets kept per symbol (covers the 30d window)
+const STATS_RETENTION_DAYS: u64 = 30;
+
+/// Upper bound on summaries returned by get_pending_intents
+const MAX_PENDING_INTENTS_PAGE: u32 = 100;
+
+/// DerivativesData fields policy rules can match on
+const POLICY_RULE_FIELDS: [&str; 7] = [
+    "instrument",
+    "symbol",
+    "side",
+    "leverage",
+    "size",
+    "collateral_token",
+    "collateral_chain",
+];
+const NUMERIC_POLICY_RULE_FIELDS: [&str; 2] = ["leverage", "size"];
+
+/// Failed attempts after which an intent stays in the dead-letter queue
+const MAX_EXECUTION_ATTEMPTS: u64 = 3;
+
+/// Upper bound on entries returned by get_dead_letter_queue
+const MAX_DEAD_LETTER_PAGE: u32 = 100;
+
+/// compute_hash over the bare canonical JSON
+const HASH_VERSION_LEGACY: u8 = 1;
+/// compute_domain_hash, prefixed with a schema-version domain tag
+const HASH_VERSION_DOMAIN_SEPARATED: u8 = 2;
+/// compute_jcs_hash over the explicit RFC 8785 serialization
+const HASH_VERSION_JCS: u8 = 3;
+
+/// Entries accepted per verify_intent_hashes_batch call, bounded by view gas
+const MAX_HASH_VERIFY_BATCH: usize = 50;
+/// Upper bound on generate_conformance_corpus cases per call
+const MAX_CONFORMANCE_CORPUS: u32 = 50;
+/// Largest gas budget a webhook registration may request
+const MAX_WEBHOOK_GAS_TGAS: u64 = 20;
+/// Gas left for log_execution itself after scheduling a webhook
+const WEBHOOK_GAS_RESERVE_TGAS: u64 = 5;
+/// Most child intents (stop-loss, take-profit, ...) one bracket parent may have
+const MAX_BRACKET_CHILDREN: usize = 4;
+/// Most taxonomy tags one intent may carry
+const MAX_TAGS_PER_INTENT: usize = 8;
+/// Longest client-supplied trace_id accepted with intent metadata
+const MAX_TRACE_ID_LEN: usize = 64;
+/// Upper bound on intents one sweep_expired_intents call removes
+const MAX_SWEEP_BATCH: u32 = 50;
+/// Upper bound on expiry index entries and empty buckets one sweep call walks
+const MAX_SWEEP_SCAN: u32 = 200;
+/// Width of one expiry index bucket: one hour in nanoseconds
+const EXPIRY_BUCKET_NS: u64 = 3_600_000_000_000;
+/// Unretried dead letters at which get_health reports red
+const HEALTH_DEAD_LETTER_RED: u64 = 10;
+/// Overdue unswept intents at which get_health reports the expiry sweep red
+const HEALTH_OVERDUE_RED: u64 = 50;
+/// Storage headroom in yoctoNEAR below which get_health reports yellow / red
+const HEALTH_STORAGE_YELLOW_YOCTO: u128 = 5 * 10u128.pow(24);
+const HEALTH_STORAGE_RED_YOCTO: u128 = 10u128.pow(24);
+/// Most documents that may be anchored against one intent
+const MAX_DOCUMENTS_PER_INTENT: usize = 16;
+/// NEP-413 prefix: 2^31 + 413, borsh-serialized ahead of the payload
+const NEP413_TAG: u32 = 2_147_484_061;
+/// Longest operator-supplied reason accepted on a treasury action, in bytes
+const MAX_ADMIN_REASON_LEN: usize = 256;
+/// Upper bound on entries returned by get_admin_actions
+const MAX_ADMIN_ACTIONS_PAGE: u32 = 100;
+
+/// Upper bound on records returned by export_account_activity
+const MAX_ACCOUNT_ACTIVITY_PAGE: u32 = 100;
+
+/// Upper bound on summaries returned by get_executions_by_venue
+const MAX_VENUE_EXECUTIONS_PAGE: u32 = 100;
+
+/// Upper bound on hashes returned by get_intents_by_trace_id
+const MAX_TRACE_INTENTS_PAGE: u32 = 100;
+
 // DeltaNEAR V2 Schema Contract - Production Ready
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
@@ -14,6 +161,56 @@ pub struct IntentMetadata {
     pub side: String,
     pub size: String,
     pub timestamp: u64,
+    /// Classification from the governance taxonomy (hedge, spec, ...)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Client correlation id; not part of the canonical intent or its hash
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    /// Set by cancel_intent; cancelled intents can no longer be executed
+    #[serde(default)]
+    pub cancelled_at: Option<u64>,
+    /// Hash of the intent this one replaced via amend_intent
+    #[serde(default)]
+    pub amends: Option<String>,
+    /// Hash of the intent that replaced this one; set together with cancelled_at
+    #[serde(default)]
+    pub amended_by: Option<String>,
+    /// Stop-loss / take-profit condition; the intent cannot execute until an
+    /// oracle reports a price that meets it. Not part of the canonical intent
+    #[serde(default)]
+    pub trigger: Option<TriggerCondition>,
+    /// Set by check_trigger once the condition is met
+    #[serde(default)]
+    pub triggered_at: Option<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TriggerCondition {
+    pub trigger_price: String,
+    pub direction: TriggerDirection,
+    /// Reference price the oracle reports, e.g. "mark" or "index"
+    pub price_source: String,
+}
+
+/// Above fires when the price rises to trigger_price or higher, Below when it
+/// falls to it or lower
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum TriggerDirection {
+    Above,
+    Below,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
@@ -27,6 +224,41 @@ pub struct ExecutionLog {
     pub fees_bps: u16,
     pub status: String,
     pub timestamp: u64,
+    #[serde(default)]
+    pub fill_source: FillSource,
+    /// Registered signed quote this fill executes; required once the solver
+    /// has a quote key
+    #[serde(default)]
+    pub quote_id: Option<String>,
+}
+
+/// Where a fill came from: routed to a venue, taken onto the solver's own
+/// book, or crossed against an RFQ counterparty
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    PartialEq,
+    Debug,
+    Default,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum FillSource {
+    #[default]
+    Venue,
+    Internalized,
+    RfqCross,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FillSourceCounts {
+    pub venue: u64,
+    pub internalized: u64,
+    pub rfq_cross: u64,
 }
 
 // V2 Schema Support - Collateral and Constraints
@@ -69,14 +301,752 @@ pub struct DerivativesData {
     pub symbol: String,
 }
 
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum CorrectionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+// Append-only correction record; the original ExecutionLog is never overwritten
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogCorrection {
+    pub correction_id: u32,
+    pub intent_hash: String,
+    pub corrected_log: ExecutionLog,
+    pub evidence_uri: String,
+    pub proposed_by: String,
+    pub proposed_at: u64,
+    /// Correction this one replaces once approved; None means the original log
+    pub supersedes: Option<u32>,
+    pub status: CorrectionStatus,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<u64>,
+}
+
+// Solver work queue filter; all fields are optional and combined with AND
+#[derive(Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingIntentFilter {
+    pub symbol: Option<String>,
+    pub instrument: Option<String>,
+    pub min_size: Option<String>,
+    /// Only intents stored by this solver
+    pub solver_id: Option<String>,
+    /// Only intents carrying this tag
+    pub tag: Option<String>,
+    /// Position in the pending index to start from
+    pub cursor: Option<u64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingIntentSummary {
+    pub intent_hash: String,
+    pub solver_id: String,
+    pub instrument: String,
+    pub symbol: String,
+    pub side: String,
+    pub size: String,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutionSummary {
+    pub intent_hash: String,
+    pub solver_id: String,
+    pub fill_price: String,
+    pub notional: String,
+    pub status: String,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VenueExecutionsPage {
+    pub executions: Vec<ExecutionSummary>,
+    // Pass back as cursor to continue; None once the venue index is exhausted
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NetExposure {
+    pub instrument: String,
+    pub symbol: String,
+    pub side: String, // "long", "short" or "flat"
+    pub size: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NettingPreview {
+    pub residuals: Vec<NetExposure>,
+    // Pairs of equal-size opposite intents that cancel each other out
+    pub offsetting_intents: Vec<(String, String)>,
+    pub unknown_intents: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventSchema {
+    pub event: String,
+    pub schema: serde_json::Value,
+}
+
+/// Value the canonicalizer fills in for an omitted constraint, with its bounds
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConstraintDefault {
+    pub field: String,
+    pub default: serde_json::Value,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+// Canonical JSON of an intent plus the schema version used for domain separation
+struct CanonicalIntent {
+    // Canonicalizer output: normalized case and decimals, defaults filled in
+    value: serde_json::Value,
+    // serde_json output, hashed by versions 1 and 2
+    json: String,
+    // Canonicalizer::to_jcs_string output, hashed by version 3
+    jcs: String,
+    schema_version: String,
+}
+
+impl CanonicalIntent {
+    fn hash(&self, hash_version: u8) -> String {
+        match hash_version {
+            HASH_VERSION_DOMAIN_SEPARATED => {
+                Canonicalizer::compute_domain_hash(&self.json, &self.schema_version)
+            }
+            HASH_VERSION_JCS => Canonicalizer::compute_jcs_hash(&self.jcs, &self.schema_version),
+            _ => Canonicalizer::compute_hash(&self.json),
+        }
+    }
+
+    /// Typed fields of the canonical form; contract state is derived from
+    /// this, never from the caller's raw JSON
+    fn intent(&self) -> Result<DerivativesIntentV2, String> {
+        serde_json::from_value(self.value.clone())
+            .map_err(|e| format!("Invalid canonical intent: {}", e))
+    }
+
+    fn hash_version_of(&self, claimed_hash: &str) -> Option<u8> {
+        [
+            HASH_VERSION_LEGACY,
+            HASH_VERSION_DOMAIN_SEPARATED,
+            HASH_VERSION_JCS,
+        ]
+        .into_iter()
+        .find(|version| self.hash(*version) == claimed_hash)
+    }
+}
+
+// Intents on one (instrument, symbol) as (size units, intent hash)
+struct NettingBook {
+    instrument: String,
+    symbol: String,
+    longs: Vec<(u128, String)>,
+    shorts: Vec<(u128, String)>,
+}
+
+// Result of a previously accepted call, replayed for retried transactions
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct IdempotencyRecord {
+    pub intent_hash: String,
+    pub result: String,
+    pub recorded_at: u64,
+}
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum DraftStatus {
+    Proposed,
+    Approved,
+    Stored,
+}
+
+// Two-party intent draft: a trader proposes, a different approver signs off
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentDraft {
+    pub draft_id: u64,
+    pub intent_hash: String,
+    pub intent: DerivativesIntentV2,
+    pub proposed_by: String,
+    pub proposed_at: u64,
+    pub expires_at: u64,
+    pub approved_by: Option<String>,
+    pub approved_at: Option<u64>,
+    pub status: DraftStatus,
+    #[serde(default)]
+    pub origin: IntentOrigin,
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    PartialEq,
+    Debug,
+    Default,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum IntentOrigin {
+    /// Proposed directly by a trader's account
+    #[default]
+    Account,
+    /// Forwarded by another contract that is itself the intent signer
+    Contract,
+}
+
+// Golden vector: raw intent JSON and the hash every SDK must derive from it
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TestVector {
+    pub name: String,
+    pub input: String,
+    pub expected_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VectorCheckResult {
+    pub matches: bool,
+    pub computed_hash: Option<String>,
+    pub error: Option<String>,
+    // Which hash_version produced the matching hash
+    pub hash_version: Option<u8>,
+}
+
+/// Generated conformance input with the contract's expected outcome; exactly
+/// one of intent_hash and error is set
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConformanceCase {
+    pub case_id: String,
+    pub mutations: Vec<String>,
+    pub input_json: String,
+    pub canonical_json: Option<String>,
+    pub intent_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntentHashClaim {
+    pub intent_json: String,
+    pub claimed_hash: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct SymbolStats {
+    pub trade_count: u64,
+    /// Lifetime notional in NOTIONAL_DECIMALS fixed-point units
+    pub total_notional: u128,
+    /// (day index, notional) buckets, oldest first
+    pub daily_notional: Vec<(u64, u128)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SymbolStatsView {
+    pub symbol: String,
+    pub trade_count: u64,
+    pub total_notional: String,
+    pub volume_24h: String,
+    pub volume_7d: String,
+    pub volume_30d: String,
+}
+
+/// How a signer generates nonces; enforced when intents enter the contract
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum NoncePolicy {
+    /// Any string, uniqueness is the client's responsibility
+    Unrestricted,
+    /// RFC 4122 version 4 UUID
+    Uuid,
+    /// Base-10 u64 strictly greater than the previous nonce
+    Counter,
+    /// Hex SHA-256 of the previous nonce; the first nonce is free-form
+    HashChain,
+}
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum RuleOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Predicate over a DerivativesData field; numeric operators apply to
+/// leverage and size, equality to any field
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RuleCondition {
+    pub field: String,
+    pub operator: RuleOperator,
+    pub value: String,
+}
+
+// Rejects an intent when every condition matches; scope limits it to one symbol
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PolicyRule {
+    pub rule_id: u32,
+    pub description: String,
+    pub scope: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+// One treasury mutation with the reason the operator gave for it
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AdminAction {
+    pub action_id: u64,
+    // Method name, e.g. "set_parameter"
+    pub action: String,
+    // What it changed, e.g. "draft_expiry_ns=3600000000000"
+    pub target: String,
+    pub actor: String,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum TimelineEvent {
+    Proposed,
+    Approved,
+    Stored,
+    Executed,
+    CorrectionProposed,
+    CorrectionApproved,
+    CorrectionRejected,
+    Committed,
+    Cancelled,
+    Expired,
+    Amended,
+    Triggered,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimelineEntry {
+    pub event: TimelineEvent,
+    pub actor: String,
+    pub timestamp: u64,
+    pub detail: Option<String>,
+}
+
+// Failed execution kept for triage until a later attempt succeeds
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeadLetterEntry {
+    pub intent_hash: String,
+    pub solver_id: String,
+    pub error_code: String,
+    pub attempts: u32,
+    pub last_attempt_at: u64,
+    pub retry_requested: bool,
+}
+
+/// FIX-inspired execution report for OMS bridges. Side uses FIX codes
+/// ("1" for long or buy, "2" for short or sell); ExecType is "0" (new) until an
+/// execution is logged, then "F" (trade) with LastPx/LastQty/CumQty filled in.
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde", rename_all = "PascalCase")]
+pub struct FixExecutionReport {
+    #[serde(rename = "ClOrdID")]
+    pub cl_ord_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub order_qty: String,
+    pub exec_type: String,
+    pub last_px: Option<String>,
+    pub last_qty: Option<String>,
+    pub cum_qty: String,
+    pub last_mkt: Option<String>,
+    pub transact_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum SigningPayloadFormat {
+    /// NEP-413 message JSON: {"message","nonce","recipient"}, nonce base64
+    Nep413,
+    /// base64url (unpadded) of {"intent_hash","intent"} for URLs and QR codes
+    DeepLink,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SigningPayload {
+    pub intent_hash: String,
+    pub format: SigningPayloadFormat,
+    pub payload: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ParameterView {
+    pub key: String,
+    pub value: u64,
+    pub unit: String,
+    pub min: u64,
+    pub max: u64,
+    pub default: u64,
+}
+
+/// Hash-only record of an intent whose trade fields stay hidden until reveal
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PrivateIntentCommitment {
+    pub intent_hash: String,
+    pub signer_id: String,
+    pub deadline: String,
+    pub committed_at: u64,
+}
+
+/// Contract method notified when an intent is filled
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WebhookTarget {
+    pub contract_id: String,
+    pub method_name: String,
+    pub gas_tgas: u64,
+}
+
+/// Argument passed as `fill` to a webhook method
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FillNotification {
+    pub intent_hash: String,
+    pub solver_id: String,
+    pub venue: String,
+    pub fill_price: String,
+    pub notional: String,
+    pub status: String,
+    pub timestamp: u64,
+}
+
+/// Entry intent plus children that stay dormant until it fills; the first
+/// child to fill cancels its siblings
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BracketGroup {
+    pub parent_hash: String,
+    pub children: Vec<String>,
+    pub signer_id: String,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TagCount {
+    pub tag: String,
+    pub intent_count: u64,
+}
+
+/// Price a solver commits to for one intent. The signed message is the JCS
+/// serialization of this struct
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SolverQuote {
+    pub intent_hash: String,
+    pub solver_id: String,
+    pub price: String,
+    pub fee_bps: u16,
+    /// Nanoseconds; fills after this time cannot reference the quote
+    pub expires_at: u64,
+}
+
+// NEP-413 message body; signers hash borsh(NEP413_TAG) ++ borsh(this)
+#[derive(BorshSerialize)]
+struct Nep413Payload {
+    message: String,
+    nonce: [u8; 32],
+    recipient: String,
+    callback_url: Option<String>,
+}
+
+/// Hash of an off-chain document (term sheet, confirmation, ...) one of the
+/// parties of an intent anchored against it
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnchoredDocument {
+    /// Lowercase hex sha256 of the document bytes
+    pub doc_hash: String,
+    pub doc_type: String,
+    pub uri: String,
+    pub anchored_by: String,
+    pub timestamp: u64,
+}
+
+/// Lifecycle state stored per intent hash. Committed -> Submitted on reveal;
+/// Submitted -> Executed, Failed, Cancelled or Expired; Failed -> Submitted on
+/// retry, or any exit Submitted has. Executed, Cancelled and Expired are final;
+/// a logged execution changes only through log corrections
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+    JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum IntentStatus {
+    Committed,
+    Submitted,
+    Executed,
+    Failed,
+    Cancelled,
+    Expired,
+}
+
+impl IntentStatus {
+    fn can_transition_to(self, to: IntentStatus) -> bool {
+        use IntentStatus::*;
+        matches!(
+            (self, to),
+            (Committed, Submitted | Expired)
+                | (Submitted, Executed | Failed | Cancelled | Expired)
+                | (Failed, Submitted | Executed | Failed | Cancelled | Expired)
+        )
+    }
+}
+
+/// Wire values of get_intent_status_code. Part of the cross-contract ABI:
+/// existing codes are never renumbered, new states get new codes
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+pub enum IntentStatusCode {
+    Unknown = 0,
+    Committed = 1,
+    Pending = 2,
+    Executed = 3,
+    Cancelled = 4,
+    Failed = 5,
+    Expired = 6,
+}
+
+/// Ordered so the overall status is the worst component status
+#[derive(
+    Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, JsonSchema,
+)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum HealthStatus {
+    Green,
+    Yellow,
+    Red,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HealthComponent {
+    pub component: String,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractHealth {
+    pub status: HealthStatus,
+    pub components: Vec<HealthComponent>,
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+pub enum StorageKey {
+    ExecutionLogs,
+    LogCorrections,
+    IntentMetadata,
+    IdempotencyRecords,
+    IntentDrafts,
+    SettlementPreferences,
+    TestVectors,
+    SymbolStats,
+    NoncePolicies,
+    LastNonces,
+    ExecutionsByVenue,
+    IntentTimelines,
+    DeadLetters,
+    AccountIntents,
+    SolverFillSources,
+    CanonicalIntents,
+    HashVersions,
+    Parameters,
+    PrivateIntents,
+    IntentWebhooks,
+    WebhookConsents,
+    BracketGroups,
+    BracketParents,
+    TagCounts,
+    TraceIntents,
+    SolverQuoteKeys,
+    SignedQuotes,
+    IntentDeadlines,
+    ExpiredIntents,
+    IntentDocuments,
+    IntentStatuses,
+    StatsSymbols,
+    DeadLetterKeys,
+    VenueExecutions { venue_hash: Vec<u8> },
+    AccountIntentList { account_hash: Vec<u8> },
+    PolicyRuleHits,
+    Topics,
+    TraceIntentList { trace_hash: Vec<u8> },
+    CanonicalHashes,
+    ExpiryBuckets,
+    ExpiryBucket { bucket: u64 },
+    AdminActions,
+    PendingIntentKeys,
+    PendingIntents,
+    ExpiryBucketCounts,
+    IdempotencyQueue,
+}
+
+/// State layout of the 1.0.0 deployment, read once by migrate
+#[derive(BorshDeserialize)]
+struct ContractV1 {
+    version: String,
+    authorized_solvers: Vec<AccountId>,
+    intent_metadata_keys: Vec<String>,
+    execution_log_keys: Vec<String>,
+}
+
 #[near(contract_state)]
 #[derive(PanicOnDefault)]
 pub struct Contract {
     pub version: String,
+    pub treasury_account_id: AccountId,
     pub authorized_solvers: Vec<AccountId>,
+    pub reviewers: Vec<AccountId>,
+    pub traders: Vec<AccountId>,
+    pub approvers: Vec<AccountId>,
     // Using Vec for now due to BorshSchema compatibility
     pub intent_metadata_keys: Vec<String>,
     pub execution_log_keys: Vec<String>,
+    pub intent_metadata: LookupMap<String, IntentMetadata>,
+    pub execution_logs: LookupMap<String, ExecutionLog>,
+    pub log_corrections: LookupMap<String, Vec<LogCorrection>>,
+    // Keyed by "<solver_id>:<idempotency_key>"
+    pub idempotency_records: LookupMap<String, IdempotencyRecord>,
+    pub intent_drafts: LookupMap<u64, IntentDraft>,
+    pub next_draft_id: u64,
+    // Token/chain an account wants PnL paid in, independent of intent collateral
+    pub settlement_preferences: LookupMap<AccountId, Collateral>,
+    // Keyed by intent schema version
+    pub test_vectors: LookupMap<String, Vec<TestVector>>,
+    pub stats_symbols: IterableSet<String>,
+    pub symbol_stats: LookupMap<String, SymbolStats>,
+    // Nonce registry keyed by signer_id
+    pub nonce_policies: LookupMap<String, NoncePolicy>,
+    pub last_nonces: LookupMap<String, String>,
+    // Intent hashes per venue in logging order, for venue reconciliation
+    pub executions_by_venue: LookupMap<String, Vector<String>>,
+    pub policy_rules: Vec<PolicyRule>,
+    pub next_policy_rule_id: u32,
+    // Intents each rule has rejected at storage time, by rule_id
+    pub policy_rule_hits: LookupMap<u32, u64>,
+    // Append-only lifecycle history per intent hash
+    pub intent_timelines: LookupMap<String, Vec<TimelineEntry>>,
+    // Hashes in first-failure order; entries no longer in dead_letters are skipped
+    pub dead_letter_keys: Vector<String>,
+    pub dead_letters: LookupMap<String, DeadLetterEntry>,
+    // Intent hashes per signer_id in storage order
+    pub account_intents: LookupMap<String, Vector<String>>,
+    pub solver_fill_sources: LookupMap<String, FillSourceCounts>,
+    // Every event topic emitted so far
+    pub topics: IterableSet<String>,
+    // Canonical JSON of intents that entered via propose_intent or store_intent_metadata
+    pub canonical_intents: LookupMap<String, String>,
+    // hash_version that produced each stored intent hash
+    pub hash_versions: LookupMap<String, u8>,
+    // Version 1 hash of each stored canonical JSON -> the hash it was stored under
+    pub canonical_hashes: LookupMap<String, String>,
+    // Overrides of PARAMETERS defaults set by the treasury
+    pub parameters: LookupMap<String, u64>,
+    // Private intents committed but not yet revealed
+    pub private_intents: LookupMap<String, PrivateIntentCommitment>,
+    pub intent_webhooks: LookupMap<String, WebhookTarget>,
+    // "<target contract>:<signer>" -> consent timestamp
+    pub webhook_consents: LookupMap<String, u64>,
+    // Keyed by parent intent hash
+    pub bracket_groups: LookupMap<String, BracketGroup>,
+    // Child intent hash -> parent intent hash
+    pub bracket_parents: LookupMap<String, String>,
+    // Governance-curated tags accepted in IntentMetadata, kept sorted
+    pub tag_taxonomy: Vec<String>,
+    // Stored intents per tag, including tags since removed from the taxonomy
+    pub tag_counts: LookupMap<String, u64>,
+    // trace_id -> intent hashes stored with it
+    pub trace_intents: LookupMap<String, Vector<String>>,
+    // ed25519 public key a solver signs quotes with
+    pub solver_quote_keys: LookupMap<String, [u8; 32]>,
+    // quote_id (hash of the signed message) -> quote
+    pub signed_quotes: LookupMap<String, SolverQuote>,
+    // Deadline in nanoseconds of every stored intent
+    pub intent_deadlines: LookupMap<String, u64>,
+    // Deadline / EXPIRY_BUCKET_NS -> hashes due in that hour, unordered
+    pub expiry_buckets: LookupMap<u64, Vector<String>>,
+    // Oldest expiry bucket the sweep has not yet emptied
+    pub expiry_cursor: u64,
+    // Swept intents -> sweep timestamp; their metadata has been removed
+    pub expired_intents: LookupMap<String, u64>,
+    // Append-only audit trail of treasury mutations, indexed by action_id
+    pub admin_actions: Vector<AdminAction>,
+    // Anchored document hashes per intent hash, in anchoring order
+    pub intent_documents: LookupMap<String, Vec<AnchoredDocument>>,
+    // Authoritative lifecycle state; see IntentStatus for allowed transitions
+    pub intent_statuses: LookupMap<String, IntentStatus>,
+    // Accounts allowed to report prices to check_trigger, sorted
+    pub oracles: Vec<AccountId>,
+    // Hashes in the order they became pending; entries no longer in
+    // pending_intents are skipped
+    pub pending_intent_keys: Vector<String>,
+    // Intents that are Submitted or Failed
+    pub pending_intents: LookupSet<String>,
+    // Dead letters with retry_requested false, kept for get_health
+    pub unretried_dead_letters: u64,
+    // Expiry bucket -> intents in it neither executed nor swept
+    pub expiry_bucket_counts: LookupMap<u64, u64>,
+    // Position -> (record key, recorded_at) in recording order, so the sweep
+    // reclaims idempotency records oldest first
+    pub idempotency_queue: LookupMap<u64, (String, u64)>,
+    // Oldest queued position and the next position to fill
+    pub idempotency_queue_head: u64,
+    pub idempotency_queue_tail: u64,
 }
 
 #[near]
@@ -89,16 +1059,126 @@ impl Contract {
         );
         Self {
             version: "1.0.0".to_string(),
+            treasury_account_id: treasury_account_id.clone(),
             authorized_solvers: vec![treasury_account_id],
+            reviewers: Vec::new(),
+            traders: Vec::new(),
+            approvers: Vec::new(),
             intent_metadata_keys: Vec::new(),
             execution_log_keys: Vec::new(),
+            intent_metadata: LookupMap::new(StorageKey::IntentMetadata),
+            execution_logs: LookupMap::new(StorageKey::ExecutionLogs),
+            log_corrections: LookupMap::new(StorageKey::LogCorrections),
+            idempotency_records: LookupMap::new(StorageKey::IdempotencyRecords),
+            intent_drafts: LookupMap::new(StorageKey::IntentDrafts),
+            next_draft_id: 0,
+            settlement_preferences: LookupMap::new(StorageKey::SettlementPreferences),
+            test_vectors: LookupMap::new(StorageKey::TestVectors),
+            stats_symbols: IterableSet::new(StorageKey::StatsSymbols),
+            symbol_stats: LookupMap::new(StorageKey::SymbolStats),
+            nonce_policies: LookupMap::new(StorageKey::NoncePolicies),
+            last_nonces: LookupMap::new(StorageKey::LastNonces),
+            executions_by_venue: LookupMap::new(StorageKey::ExecutionsByVenue),
+            policy_rules: Vec::new(),
+            next_policy_rule_id: 0,
+            policy_rule_hits: LookupMap::new(StorageKey::PolicyRuleHits),
+            intent_timelines: LookupMap::new(StorageKey::IntentTimelines),
+            dead_letter_keys: Vector::new(StorageKey::DeadLetterKeys),
+            dead_letters: LookupMap::new(StorageKey::DeadLetters),
+            account_intents: LookupMap::new(StorageKey::AccountIntents),
+            solver_fill_sources: LookupMap::new(StorageKey::SolverFillSources),
+            topics: IterableSet::new(StorageKey::Topics),
+            canonical_intents: LookupMap::new(StorageKey::CanonicalIntents),
+            hash_versions: LookupMap::new(StorageKey::HashVersions),
+            canonical_hashes: LookupMap::new(StorageKey::CanonicalHashes),
+            parameters: LookupMap::new(StorageKey::Parameters),
+            private_intents: LookupMap::new(StorageKey::PrivateIntents),
+            intent_webhooks: LookupMap::new(StorageKey::IntentWebhooks),
+            webhook_consents: LookupMap::new(StorageKey::WebhookConsents),
+            bracket_groups: LookupMap::new(StorageKey::BracketGroups),
+            bracket_parents: LookupMap::new(StorageKey::BracketParents),
+            tag_taxonomy: Vec::new(),
+            tag_counts: LookupMap::new(StorageKey::TagCounts),
+            trace_intents: LookupMap::new(StorageKey::TraceIntents),
+            solver_quote_keys: LookupMap::new(StorageKey::SolverQuoteKeys),
+            signed_quotes: LookupMap::new(StorageKey::SignedQuotes),
+            intent_deadlines: LookupMap::new(StorageKey::IntentDeadlines),
+            expiry_buckets: LookupMap::new(StorageKey::ExpiryBuckets),
+            expiry_cursor: env::block_timestamp() / EXPIRY_BUCKET_NS,
+            expired_intents: LookupMap::new(StorageKey::ExpiredIntents),
+            admin_actions: Vector::new(StorageKey::AdminActions),
+            intent_documents: LookupMap::new(StorageKey::IntentDocuments),
+            intent_statuses: LookupMap::new(StorageKey::IntentStatuses),
+            oracles: Vec::new(),
+            pending_intent_keys: Vector::new(StorageKey::PendingIntentKeys),
+            pending_intents: LookupSet::new(StorageKey::PendingIntents),
+            unretried_dead_letters: 0,
+            expiry_bucket_counts: LookupMap::new(StorageKey::ExpiryBucketCounts),
+            idempotency_queue: LookupMap::new(StorageKey::IdempotencyQueue),
+            idempotency_queue_head: 0,
+            idempotency_queue_tail: 0,
         }
     }
 
+    /// Upgrade a 1.0.0 deployment, whose state is only the version, solver
+    /// list and key lists. Every collection added since starts empty and the
+    /// treasury joins the solvers, as in new
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(treasury_account_id: AccountId) -> Self {
+        let old: ContractV1 = env::state_read()
+            .unwrap_or_else(|| env::panic_str("No 1.0.0 contract state to migrate"));
+        Self::from_v1(old, treasury_account_id)
+    }
+
     pub fn get_schema_version(&self) -> String {
         "2.0.0".to_string() // V2.0.0 - Breaking change with collateral object
     }
 
+    /// Current value, unit and bounds of every governance parameter
+    pub fn get_parameters(&self) -> Vec<ParameterView> {
+        PARAMETERS
+            .iter()
+            .map(|spec| ParameterView {
+                key: spec.key.to_string(),
+                value: self.parameter(spec.key),
+                unit: spec.unit.to_string(),
+                min: spec.min,
+                max: spec.max,
+                default: spec.default,
+            })
+            .collect()
+    }
+
+    pub fn set_parameter(&mut self, key: String, value: u64, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can set parameters"
+        );
+        Self::check_admin_reason(&reason);
+        let spec = PARAMETERS
+            .iter()
+            .find(|spec| spec.key == key)
+            .unwrap_or_else(|| env::panic_str(&format!("Unknown parameter: {}", key)));
+        require!(
+            value >= spec.min && value <= spec.max,
+            format!(
+                "Parameter {} must be within [{}, {}] {}",
+                key, spec.min, spec.max, spec.unit
+            )
+        );
+        log!(
+            "Parameter {} changed from {} to {} {}: {}",
+            key,
+            self.parameter(spec.key),
+            value,
+            spec.unit,
+            reason
+        );
+        self.record_admin_action("set_parameter", format!("{}={}", key, value), reason);
+        self.parameters.insert(key, value);
+    }
+
     pub fn get_contract_version(&self) -> String {
         self.version.clone()
     }
@@ -107,62 +1187,2069 @@ impl Contract {
         "67e4874cb41e405be0d3e532341adace4137cb30d59b42cb480823624bb4503f".to_string()
     }
 
+    /// Sorted by account id
     pub fn get_authorized_solvers(&self) -> Vec<AccountId> {
         self.authorized_solvers.clone()
     }
 
-    pub fn add_authorized_solver(&mut self, solver_id: AccountId) {
-        if !self.authorized_solvers.contains(&solver_id) {
-            self.authorized_solvers.push(solver_id.clone());
-            log!("Added authorized solver: {}", solver_id);
+    pub fn add_authorized_solver(&mut self, solver_id: AccountId, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add authorized solvers"
+        );
+        Self::check_admin_reason(&reason);
+        if insert_sorted(&mut self.authorized_solvers, solver_id.clone()) {
+            log!("Added authorized solver: {} ({})", solver_id, reason);
+            self.record_admin_action("add_authorized_solver", solver_id.to_string(), reason);
         }
     }
 
-    pub fn store_intent_metadata(
-        &mut self,
-        intent_hash: String,
-        metadata: IntentMetadata,
-    ) -> String {
-        log!("Storing V2 intent metadata for hash: {}", intent_hash);
-        if !self.intent_metadata_keys.contains(&intent_hash) {
-            self.intent_metadata_keys.push(intent_hash.clone());
+    /// Sorted by account id
+    pub fn get_reviewers(&self) -> Vec<AccountId> {
+        self.reviewers.clone()
+    }
+
+    pub fn add_reviewer(&mut self, reviewer_id: AccountId, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add reviewers"
+        );
+        Self::check_admin_reason(&reason);
+        if insert_sorted(&mut self.reviewers, reviewer_id.clone()) {
+            log!("Added reviewer: {} ({})", reviewer_id, reason);
+            self.record_admin_action("add_reviewer", reviewer_id.to_string(), reason);
         }
-        format!(
-            "Stored V2 intent {} for solver {}",
-            intent_hash, metadata.solver_id
-        )
     }
 
-    pub fn get_intent_metadata(&self, intent_hash: String) -> Option<String> {
-        if self.intent_metadata_keys.contains(&intent_hash) {
-            Some(format!("Intent metadata found for: {}", intent_hash))
-        } else {
-            None
+    pub fn add_trader(&mut self, trader_id: AccountId, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add traders"
+        );
+        Self::check_admin_reason(&reason);
+        if insert_sorted(&mut self.traders, trader_id.clone()) {
+            log!("Added trader: {} ({})", trader_id, reason);
+            self.record_admin_action("add_trader", trader_id.to_string(), reason);
         }
     }
 
-    pub fn log_execution(&mut self, intent_hash: String, log: ExecutionLog) -> String {
-        log!("Logging V2 execution for intent: {}", intent_hash);
-        if !self.execution_log_keys.contains(&intent_hash) {
-            self.execution_log_keys.push(intent_hash.clone());
+    pub fn add_approver(&mut self, approver_id: AccountId, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add approvers"
+        );
+        Self::check_admin_reason(&reason);
+        if insert_sorted(&mut self.approvers, approver_id.clone()) {
+            log!("Added approver: {} ({})", approver_id, reason);
+            self.record_admin_action("add_approver", approver_id.to_string(), reason);
         }
-        format!(
-            "Logged V2 execution {} at venue {} with status {}",
-            intent_hash, log.venue, log.status
-        )
     }
 
-    pub fn get_execution_log(&self, intent_hash: String) -> Option<String> {
-        if self.execution_log_keys.contains(&intent_hash) {
-            Some(format!("Execution log found for: {}", intent_hash))
-        } else {
-            None
+    /// Sorted by account id
+    pub fn get_oracles(&self) -> Vec<AccountId> {
+        self.oracles.clone()
+    }
+
+    pub fn add_oracle(&mut self, oracle_id: AccountId, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add oracles"
+        );
+        Self::check_admin_reason(&reason);
+        if insert_sorted(&mut self.oracles, oracle_id.clone()) {
+            log!("Added oracle: {} ({})", oracle_id, reason);
+            self.record_admin_action("add_oracle", oracle_id.to_string(), reason);
         }
     }
 
-    // V2 Schema validation helper
-    #[handle_result]
-    pub fn validate_v2_intent(&self, intent: DerivativesIntentV2) -> Result<String, String> {
+    pub fn get_intent_draft(&self, draft_id: u64) -> Option<IntentDraft> {
+        self.intent_drafts.get(&draft_id).cloned()
+    }
+
+    /// Trader proposes a V2 intent; it only becomes metadata after approval
+    pub fn propose_intent(&mut self, intent_json: String) -> u64 {
+        let trader_id = env::predecessor_account_id();
+        require!(
+            self.traders.contains(&trader_id),
+            "Only traders can propose intents"
+        );
+        self.create_draft(&intent_json, trader_id, IntentOrigin::Account)
+    }
+
+    /// Programmatic submission by a vault, DAO or other contract. The calling
+    /// contract must be a registered trader and is required to be the
+    /// intent's signer_id; the draft still needs an approver.
+    pub fn submit_intent_from_contract(&mut self, intent_json: String) -> u64 {
+        let contract_id = env::predecessor_account_id();
+        require!(
+            contract_id != env::signer_account_id(),
+            "Only callable from another contract"
+        );
+        require!(
+            self.traders.contains(&contract_id),
+            "Only traders can propose intents"
+        );
+        self.create_draft(&intent_json, contract_id, IntentOrigin::Contract)
+    }
+
+    /// Second signature; the approver must differ from the proposing trader
+    pub fn approve_intent(&mut self, draft_id: u64) {
+        let approver_id = env::predecessor_account_id();
+        require!(
+            self.approvers.contains(&approver_id),
+            "Only approvers can approve intents"
+        );
+        let draft = self
+            .intent_drafts
+            .get_mut(&draft_id)
+            .unwrap_or_else(|| env::panic_str("Unknown draft"));
+        require!(
+            draft.status == DraftStatus::Proposed,
+            "Draft is not awaiting approval"
+        );
+        require!(
+            env::block_timestamp() <= draft.expires_at,
+            "Draft has expired"
+        );
+        require!(
+            draft.proposed_by != approver_id.as_str(),
+            "Proposer cannot approve their own draft"
+        );
+
+        draft.status = DraftStatus::Approved;
+        draft.approved_by = Some(approver_id.to_string());
+        draft.approved_at = Some(env::block_timestamp());
+        let intent_hash = draft.intent_hash.clone();
+        log!("Approved intent draft {} by {}", draft_id, approver_id);
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Approved,
+            Some(format!("draft {}", draft_id)),
+        );
+    }
+
+    /// Authorized solver turns an approved draft into stored intent metadata
+    pub fn store_draft_metadata(&mut self, draft_id: u64) -> String {
+        let solver_id = env::predecessor_account_id();
+        require!(
+            self.authorized_solvers.contains(&solver_id),
+            "Only authorized solvers can store intent metadata"
+        );
+        let draft = self
+            .intent_drafts
+            .get_mut(&draft_id)
+            .unwrap_or_else(|| env::panic_str("Unknown draft"));
+        require!(
+            draft.status == DraftStatus::Approved,
+            "Only approved drafts can be stored"
+        );
+        require!(
+            env::block_timestamp() <= draft.expires_at,
+            "Draft has expired"
+        );
+        draft.status = DraftStatus::Stored;
+
+        let metadata = IntentMetadata {
+            intent_hash: draft.intent_hash.clone(),
+            solver_id: solver_id.to_string(),
+            instrument: draft.intent.derivatives.instrument.clone(),
+            symbol: draft.intent.derivatives.symbol.clone(),
+            side: draft.intent.derivatives.side.clone(),
+            size: draft.intent.derivatives.size.clone(),
+            timestamp: env::block_timestamp(),
+            tags: Vec::new(),
+            trace_id: None,
+            cancelled_at: None,
+            amends: None,
+            amended_by: None,
+            trigger: None,
+            triggered_at: None,
+        };
+        let intent_hash = draft.intent_hash.clone();
+        let signer_id = draft.intent.signer_id.clone();
+        let deadline = draft.intent.deadline.clone();
+        self.record_deadline(&intent_hash, &deadline);
+        self.index_account_intent(&signer_id, &intent_hash);
+        // The draft's hash was computed from its canonical JSON at proposal
+        self.insert_intent_metadata(intent_hash, metadata)
+    }
+
+    /// Metadata must agree with the intent it describes: intent_json has to
+    /// canonicalize to intent_hash and carry the same trade fields. The first
+    /// store consumes the signer's nonce under its nonce policy. An intent a
+    /// policy rule matches is not stored; the call returns the rejection so
+    /// the rule's hit count persists
+    pub fn store_intent_metadata(
+        &mut self,
+        intent_hash: String,
+        metadata: IntentMetadata,
+        intent_json: String,
+    ) -> String {
+        require!(
+            !self.private_intents.contains_key(&intent_hash),
+            "Intent has a private commitment; disclose it with reveal_intent_metadata"
+        );
+        let canonical = Self::canonical_intent(&intent_json).unwrap_or_else(|e| env::panic_str(&e));
+        let hash_version = canonical
+            .hash_version_of(&intent_hash)
+            .filter(|_| metadata.intent_hash == intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent hash does not match canonical intent JSON"));
+        let intent = canonical.intent().unwrap_or_else(|e| env::panic_str(&e));
+        let derivatives = &intent.derivatives;
+        for (field, stored, canonical) in [
+            ("instrument", &metadata.instrument, &derivatives.instrument),
+            ("symbol", &metadata.symbol, &derivatives.symbol),
+            ("side", &metadata.side, &derivatives.side),
+            ("size", &metadata.size, &derivatives.size),
+        ] {
+            let agrees = match field {
+                "size" => parse_units(stored, SIZE_DECIMALS)
+                    .is_some_and(|units| Some(units) == parse_units(canonical, SIZE_DECIMALS)),
+                _ => stored.trim().eq_ignore_ascii_case(canonical),
+            };
+            if !agrees {
+                env::panic_str(&format!(
+                    "Metadata {} {} does not match intent {}",
+                    field, stored, canonical
+                ));
+            }
+        }
+        // Stored in canonical form so later reads need not normalize
+        let mut metadata = metadata;
+        metadata.instrument = derivatives.instrument.clone();
+        metadata.symbol = derivatives.symbol.clone();
+        metadata.side = derivatives.side.clone();
+        metadata.size = derivatives.size.clone();
+        if let Some(rule) = self.matching_policy_rule(&intent) {
+            let (rule_id, rejection) = (rule.rule_id, Self::policy_rejection(rule));
+            let hits = self.policy_rule_hits.get(&rule_id).copied().unwrap_or(0);
+            self.policy_rule_hits.insert(rule_id, hits + 1);
+            log!("{} for intent {}", rejection, intent_hash);
+            return rejection;
+        }
+        self.claim_canonical_hash(&canonical, &intent_hash);
+        // Re-storing the same hash updates metadata and does not reuse the nonce
+        let first_store = !self.intent_metadata.contains_key(&intent_hash);
+        if first_store {
+            if let Err(e) = self.check_nonce(intent.signer_id.clone(), intent.nonce.clone()) {
+                env::panic_str(&e);
+            }
+            self.last_nonces
+                .insert(intent.signer_id.clone(), intent.nonce.clone());
+        }
+        self.record_deadline(&intent_hash, &intent.deadline);
+        self.index_account_intent(&intent.signer_id, &intent_hash);
+        self.canonical_intents
+            .insert(intent_hash.clone(), canonical.json);
+        self.hash_versions.insert(intent_hash.clone(), hash_version);
+        let message = self.insert_intent_metadata(intent_hash.clone(), metadata);
+        if first_store {
+            let signer_id: AccountId = intent
+                .signer_id
+                .parse()
+                .unwrap_or_else(|_| env::panic_str("Invalid signer_id"));
+            let market = self.event_market(&intent_hash);
+            let topic = EventEmitter::emit_intent_submitted(
+                intent_hash,
+                signer_id,
+                intent.derivatives.side,
+                intent.derivatives.size,
+                &market,
+            );
+            self.record_topic(topic);
+        }
+        message
+    }
+
+    /// Privacy tier: only the hash, signer and deadline are public until the
+    /// intent is revealed with reveal_intent_metadata
+    pub fn store_private_intent(
+        &mut self,
+        intent_hash: String,
+        signer_id: String,
+        deadline: String,
+    ) -> String {
+        // Committed in canonical form so reveal compares like with like
+        let signer_id =
+            Canonicalizer::normalize_signer_id(&signer_id).unwrap_or_else(|e| env::panic_str(&e));
+        let deadline =
+            Canonicalizer::normalize_timestamp(&deadline).unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            env::predecessor_account_id().as_str() == signer_id,
+            "Only the signer can commit a private intent"
+        );
+        require!(
+            !self.intent_metadata.contains_key(&intent_hash)
+                && !self.private_intents.contains_key(&intent_hash),
+            "Intent already stored"
+        );
+        log!(
+            "Storing private intent commitment for hash: {}",
+            intent_hash
+        );
+        // Unrevealed commitments expire and are swept like stored intents
+        self.record_deadline(&intent_hash, &deadline);
+        self.index_account_intent(&signer_id, &intent_hash);
+        self.set_intent_status(&intent_hash, IntentStatus::Committed);
+        self.record_timeline(&intent_hash, TimelineEvent::Committed, None);
+        self.private_intents.insert(
+            intent_hash.clone(),
+            PrivateIntentCommitment {
+                intent_hash: intent_hash.clone(),
+                signer_id,
+                deadline,
+                committed_at: env::block_timestamp(),
+            },
+        );
+        format!("Stored private intent {}", intent_hash)
+    }
+
+    /// Disclose a private intent; it must hash to the commitment and its
+    /// canonical form must carry the committed signer and deadline
+    pub fn reveal_intent_metadata(
+        &mut self,
+        intent_hash: String,
+        metadata: IntentMetadata,
+        intent_json: String,
+    ) -> String {
+        let commitment = self
+            .private_intents
+            .get(&intent_hash)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("No private intent for this hash"));
+        let intent = Self::canonical_intent(&intent_json)
+            .and_then(|canonical| canonical.intent())
+            .unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            intent.signer_id == commitment.signer_id && intent.deadline == commitment.deadline,
+            "Revealed intent does not match commitment"
+        );
+        self.private_intents.remove(&intent_hash);
+        self.store_intent_metadata(intent_hash, metadata, intent_json)
+    }
+
+    pub fn get_private_intent(&self, intent_hash: String) -> Option<PrivateIntentCommitment> {
+        self.private_intents.get(&intent_hash).cloned()
+    }
+
+    /// Called by a webhook target contract to accept notifications for
+    /// intents signed by account_id
+    pub fn consent_to_webhooks(&mut self, account_id: AccountId) {
+        let key = format!("{}:{}", env::predecessor_account_id(), account_id);
+        self.webhook_consents.insert(key, env::block_timestamp());
+    }
+
+    pub fn revoke_webhook_consent(&mut self, account_id: AccountId) {
+        let key = format!("{}:{}", env::predecessor_account_id(), account_id);
+        self.webhook_consents.remove(&key);
+    }
+
+    /// Intent signer registers a best-effort fill callback; the target must
+    /// have consented to calls on the signer's behalf
+    pub fn register_intent_webhook(
+        &mut self,
+        intent_hash: String,
+        contract_id: AccountId,
+        method_name: String,
+        gas_tgas: u64,
+    ) {
+        let signer = self
+            .intent_signer(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent not found"));
+        require!(
+            env::predecessor_account_id().as_str() == signer,
+            "Only the intent signer can register a webhook"
+        );
+        require!(
+            self.webhook_consents
+                .contains_key(&format!("{}:{}", contract_id, signer)),
+            "Webhook target has not consented"
+        );
+        require!(
+            (1..=MAX_WEBHOOK_GAS_TGAS).contains(&gas_tgas),
+            format!("gas_tgas must be within [1, {}]", MAX_WEBHOOK_GAS_TGAS)
+        );
+        self.intent_webhooks.insert(
+            intent_hash,
+            WebhookTarget {
+                contract_id: contract_id.to_string(),
+                method_name,
+                gas_tgas,
+            },
+        );
+    }
+
+    pub fn get_intent_webhook(&self, intent_hash: String) -> Option<WebhookTarget> {
+        self.intent_webhooks.get(&intent_hash).cloned()
+    }
+
+    /// Signer links stored intents into a bracket: children can only be
+    /// executed after the parent, and only one child may fill
+    pub fn create_bracket_group(&mut self, parent_hash: String, children: Vec<String>) {
+        require!(
+            !children.is_empty() && children.len() <= MAX_BRACKET_CHILDREN,
+            format!("A bracket needs 1 to {} children", MAX_BRACKET_CHILDREN)
+        );
+        let signer = env::predecessor_account_id().to_string();
+        for hash in std::iter::once(&parent_hash).chain(children.iter()) {
+            require!(
+                self.intent_signer(hash).as_deref() == Some(signer.as_str()),
+                format!("Intent {} is not stored or not signed by caller", hash)
+            );
+            require!(
+                !self.execution_logs.contains_key(hash),
+                format!("Intent {} is already executed", hash)
+            );
+            require!(
+                !self.bracket_groups.contains_key(hash) && !self.bracket_parents.contains_key(hash),
+                format!("Intent {} is already in a bracket", hash)
+            );
+        }
+        for (i, child) in children.iter().enumerate() {
+            require!(
+                *child != parent_hash && !children[..i].contains(child),
+                "Duplicate intent in bracket"
+            );
+        }
+        for child in &children {
+            self.bracket_parents
+                .insert(child.clone(), parent_hash.clone());
+        }
+        log!(
+            "Bracket {} created with {} children",
+            parent_hash,
+            children.len()
+        );
+        self.bracket_groups.insert(
+            parent_hash.clone(),
+            BracketGroup {
+                parent_hash,
+                children,
+                signer_id: signer,
+                created_at: env::block_timestamp(),
+            },
+        );
+    }
+
+    /// Oracle reports the current price for the intent's price_source; returns
+    /// true once the trigger has fired, which makes the intent executable
+    pub fn check_trigger(&mut self, intent_hash: String, price: String) -> bool {
+        let oracle_id = env::predecessor_account_id();
+        require!(
+            self.oracles.contains(&oracle_id),
+            "Only oracles can check triggers"
+        );
+        require!(
+            !matches!(
+                self.intent_status(&intent_hash),
+                Some(IntentStatus::Cancelled | IntentStatus::Executed)
+            ),
+            "Cancelled or executed intents cannot be triggered"
+        );
+        let price_units = parse_units(&price, PRICE_DECIMALS)
+            .unwrap_or_else(|| env::panic_str(&format!("Invalid price: {}", price)));
+        let metadata = self
+            .intent_metadata
+            .get_mut(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent not found"));
+        let trigger = metadata
+            .trigger
+            .as_ref()
+            .unwrap_or_else(|| env::panic_str("Intent has no trigger"));
+        if metadata.triggered_at.is_some() {
+            return true;
+        }
+        let trigger_units = parse_units(&trigger.trigger_price, PRICE_DECIMALS).unwrap_or(0);
+        let met = match trigger.direction {
+            TriggerDirection::Above => price_units >= trigger_units,
+            TriggerDirection::Below => price_units <= trigger_units,
+        };
+        if !met {
+            return false;
+        }
+        metadata.triggered_at = Some(env::block_timestamp());
+        let source = trigger.price_source.clone();
+
+        log!(
+            "Triggered intent {} at {} {}{}",
+            intent_hash,
+            source,
+            price,
+            self.trace_suffix(&intent_hash)
+        );
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Triggered,
+            Some(format!("{} {}", source, price)),
+        );
+        true
+    }
+
+    /// The bracket an intent belongs to, looked up by parent or child hash
+    pub fn get_bracket_group(&self, intent_hash: String) -> Option<BracketGroup> {
+        let parent = self
+            .bracket_parents
+            .get(&intent_hash)
+            .unwrap_or(&intent_hash);
+        self.bracket_groups.get(parent).cloned()
+    }
+
+    /// Authorized solver sets the ed25519 key (hex) it signs quotes with.
+    /// From then on its execution logs must reference a signed quote
+    pub fn register_quote_key(&mut self, public_key: String) {
+        let solver_id = env::predecessor_account_id();
+        require!(
+            self.authorized_solvers.contains(&solver_id),
+            "Only authorized solvers can register quote keys"
+        );
+        let key: [u8; 32] = hex::decode(&public_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .unwrap_or_else(|| env::panic_str("Quote key must be 32 bytes of hex"));
+        log!("Quote key registered for solver {}", solver_id);
+        self.solver_quote_keys.insert(solver_id.to_string(), key);
+    }
+
+    /// Permissionless: store a quote after checking the solver's NEP-413
+    /// signature over it, addressed to this contract. Returns the quote_id
+    pub fn register_signed_quote(
+        &mut self,
+        quote: SolverQuote,
+        nonce: String,
+        signature: String,
+    ) -> String {
+        let key = *self
+            .solver_quote_keys
+            .get(&quote.solver_id)
+            .unwrap_or_else(|| env::panic_str("Solver has no quote key"));
+        require!(
+            env::block_timestamp() <= quote.expires_at,
+            "Quote has expired"
+        );
+        let nonce: [u8; 32] = hex::decode(&nonce)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .unwrap_or_else(|| env::panic_str("Nonce must be 32 bytes of hex"));
+        let signature: [u8; 64] = hex::decode(&signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .unwrap_or_else(|| env::panic_str("Signature must be 64 bytes of hex"));
+
+        let message = serde_json::to_value(&quote)
+            .map_err(|e| e.to_string())
+            .and_then(|value| Canonicalizer::to_jcs_string(&value))
+            .unwrap_or_else(|e| env::panic_str(&format!("Failed to encode quote: {}", e)));
+        let payload = Nep413Payload {
+            message: message.clone(),
+            nonce,
+            recipient: env::current_account_id().to_string(),
+            callback_url: None,
+        };
+        let mut signed = near_sdk::borsh::to_vec(&NEP413_TAG)
+            .unwrap_or_else(|_| env::panic_str("Failed to encode NEP-413 tag"));
+        signed.extend(
+            near_sdk::borsh::to_vec(&payload)
+                .unwrap_or_else(|_| env::panic_str("Failed to encode NEP-413 payload")),
+        );
+        require!(
+            env::ed25519_verify(&signature, &env::sha256(&signed), &key),
+            "Invalid quote signature"
+        );
+
+        let quote_id = Canonicalizer::compute_hash(&message);
+        log!(
+            "Registered quote {} from {} for intent {}",
+            quote_id,
+            quote.solver_id,
+            quote.intent_hash
+        );
+        self.signed_quotes.insert(quote_id.clone(), quote);
+        quote_id
+    }
+
+    pub fn get_signed_quote(&self, quote_id: String) -> Option<SolverQuote> {
+        self.signed_quotes.get(&quote_id).cloned()
+    }
+
+    /// Withdraw a stored intent before execution; allowed for its signer and
+    /// for the solver that stored it
+    pub fn cancel_intent(&mut self, intent_hash: String) {
+        let caller = env::predecessor_account_id();
+        let signer = self.intent_signer(&intent_hash);
+        let authorized_solver = self.authorized_solvers.contains(&caller);
+        let metadata = self
+            .intent_metadata
+            .get_mut(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent not found"));
+        require!(
+            signer.as_deref() == Some(caller.as_str())
+                || (authorized_solver && metadata.solver_id == caller.as_str()),
+            "Only the intent signer or its solver can cancel"
+        );
+        require!(
+            metadata.cancelled_at.is_none(),
+            "Intent is already cancelled"
+        );
+        require!(
+            !self.execution_logs.contains_key(&intent_hash),
+            "Executed intents cannot be cancelled"
+        );
+        metadata.cancelled_at = Some(env::block_timestamp());
+        self.set_intent_status(&intent_hash, IntentStatus::Cancelled);
+
+        log!(
+            "Cancelled intent {} by {}{}",
+            intent_hash,
+            caller,
+            self.trace_suffix(&intent_hash)
+        );
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Cancelled,
+            Some(caller.to_string()),
+        );
+        let market = self.event_market(&intent_hash);
+        let topic = EventEmitter::emit_intent_cancelled(intent_hash, caller, &market);
+        self.record_topic(topic);
+    }
+
+    /// Replace a pending intent with a new version from the same signer. The
+    /// old intent is cancelled and both sides of the link are kept in metadata;
+    /// solver, tags, trace_id and trigger carry over to the new hash
+    pub fn amend_intent(&mut self, old_hash: String, new_intent_json: String) -> String {
+        let caller = env::predecessor_account_id();
+        let old = self
+            .intent_metadata
+            .get(&old_hash)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("Intent not found"));
+        require!(
+            self.intent_signer(&old_hash).as_deref() == Some(caller.as_str()),
+            "Only the intent signer can amend"
+        );
+        require!(
+            old.cancelled_at.is_none(),
+            "Cancelled intents cannot be amended"
+        );
+        require!(
+            !self.execution_logs.contains_key(&old_hash),
+            "Executed intents cannot be amended"
+        );
+
+        let canonical =
+            Self::canonical_intent(&new_intent_json).unwrap_or_else(|e| env::panic_str(&e));
+        let new_hash = canonical.hash(HASH_VERSION_LEGACY);
+        require!(
+            !self.intent_metadata.contains_key(&new_hash),
+            "Amended intent is already stored"
+        );
+        let intent = canonical.intent().unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            intent.signer_id == caller.as_str(),
+            "Amended intent must keep the same signer"
+        );
+        if let Err(e) = self.validate_v2_intent(intent.clone()) {
+            env::panic_str(&e);
+        }
+        if let Err(e) = self.check_nonce(intent.signer_id.clone(), intent.nonce.clone()) {
+            env::panic_str(&e);
+        }
+        self.last_nonces
+            .insert(intent.signer_id.clone(), intent.nonce.clone());
+
+        self.record_deadline(&new_hash, &intent.deadline);
+        self.index_account_intent(&intent.signer_id, &new_hash);
+        self.claim_canonical_hash(&canonical, &new_hash);
+        self.canonical_intents
+            .insert(new_hash.clone(), canonical.json);
+        self.hash_versions
+            .insert(new_hash.clone(), HASH_VERSION_LEGACY);
+        let derivatives = intent.derivatives;
+        self.insert_intent_metadata(
+            new_hash.clone(),
+            IntentMetadata {
+                intent_hash: new_hash.clone(),
+                solver_id: old.solver_id,
+                instrument: derivatives.instrument,
+                symbol: derivatives.symbol,
+                side: derivatives.side,
+                size: derivatives.size,
+                timestamp: env::block_timestamp(),
+                tags: old.tags,
+                trace_id: old.trace_id,
+                cancelled_at: None,
+                amends: None,
+                amended_by: None,
+                trigger: old.trigger,
+                triggered_at: None,
+            },
+        );
+        if let Some(metadata) = self.intent_metadata.get_mut(&new_hash) {
+            metadata.amends = Some(old_hash.clone());
+        }
+        if let Some(metadata) = self.intent_metadata.get_mut(&old_hash) {
+            metadata.cancelled_at = Some(env::block_timestamp());
+            metadata.amended_by = Some(new_hash.clone());
+        }
+        self.set_intent_status(&old_hash, IntentStatus::Cancelled);
+
+        log!(
+            "Amended intent {} -> {}{}",
+            old_hash,
+            new_hash,
+            self.trace_suffix(&new_hash)
+        );
+        self.record_timeline(&old_hash, TimelineEvent::Amended, Some(new_hash.clone()));
+        let market = self.event_market(&new_hash);
+        let topic = EventEmitter::emit_intent_amended(old_hash, new_hash.clone(), caller, &market);
+        self.record_topic(topic);
+        new_hash
+    }
+
+    /// Record the hash of an off-chain document agreed for an intent; only
+    /// the intent signer and its solver may anchor. Anchors cannot be removed
+    pub fn anchor_document(
+        &mut self,
+        intent_hash: String,
+        doc_hash: String,
+        doc_type: String,
+        uri: String,
+    ) {
+        let caller = env::predecessor_account_id();
+        let metadata = self
+            .intent_metadata
+            .get(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent not found"));
+        require!(
+            (metadata.solver_id == caller.as_str() && self.authorized_solvers.contains(&caller))
+                || self.intent_signer(&intent_hash).as_deref() == Some(caller.as_str()),
+            "Only the intent signer or its solver can anchor documents"
+        );
+        require!(
+            doc_hash.len() == 64
+                && doc_hash
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)),
+            "doc_hash must be a lowercase hex sha256"
+        );
+        require!(!doc_type.is_empty(), "doc_type is required");
+
+        let mut documents = self.get_intent_documents(intent_hash.clone());
+        require!(
+            !documents.iter().any(|doc| doc.doc_hash == doc_hash),
+            "Document already anchored for this intent"
+        );
+        require!(
+            documents.len() < MAX_DOCUMENTS_PER_INTENT,
+            format!("At most {} documents per intent", MAX_DOCUMENTS_PER_INTENT)
+        );
+        log!(
+            "Anchored {} document {} to intent {}",
+            doc_type,
+            doc_hash,
+            intent_hash
+        );
+        let market = self.event_market(&intent_hash);
+        let topic = EventEmitter::emit_document_anchored(
+            intent_hash.clone(),
+            doc_hash.clone(),
+            doc_type.clone(),
+            caller.clone(),
+            &market,
+        );
+        self.record_topic(topic);
+        documents.push(AnchoredDocument {
+            doc_hash,
+            doc_type,
+            uri,
+            anchored_by: caller.to_string(),
+            timestamp: env::block_timestamp(),
+        });
+        self.intent_documents.insert(intent_hash, documents);
+    }
+
+    /// Documents anchored to an intent in anchoring order
+    pub fn get_intent_documents(&self, intent_hash: String) -> Vec<AnchoredDocument> {
+        self.intent_documents
+            .get(&intent_hash)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The anchor for doc_hash if it was anchored to the intent; hash the
+    /// document locally and pass the result to check integrity
+    pub fn verify_document(
+        &self,
+        intent_hash: String,
+        doc_hash: String,
+    ) -> Option<AnchoredDocument> {
+        self.intent_documents
+            .get(&intent_hash)?
+            .iter()
+            .find(|doc| doc.doc_hash == doc_hash)
+            .cloned()
+    }
+
+    /// Permissionless: remove up to limit unexecuted intents whose deadline
+    /// has passed, keeping only a tombstone and their timeline. Walks the
+    /// expiry index from the oldest unswept hour. Also reclaims up to limit
+    /// idempotency records past their TTL. Returns the number of intents swept
+    pub fn sweep_expired_intents(&mut self, limit: Option<u32>) -> u32 {
+        let limit = limit.unwrap_or(MAX_SWEEP_BATCH).min(MAX_SWEEP_BATCH) as usize;
+        let now = env::block_timestamp();
+        let now_bucket = now / EXPIRY_BUCKET_NS;
+        let mut expired: Vec<(String, u64)> = Vec::new();
+        let mut scanned = 0;
+        while expired.len() < limit && scanned < MAX_SWEEP_SCAN && self.expiry_cursor <= now_bucket
+        {
+            let bucket = self.expiry_cursor;
+            let mut position = 0;
+            while expired.len() < limit && scanned < MAX_SWEEP_SCAN {
+                let Some(hash) = self
+                    .expiry_buckets
+                    .get(&bucket)
+                    .and_then(|hashes| hashes.get(position))
+                    .cloned()
+                else {
+                    break;
+                };
+                scanned += 1;
+                let deadline = self.intent_deadlines.get(&hash).copied();
+                // Only the current hour holds intents that are not yet due
+                if !self.execution_logs.contains_key(&hash) && deadline.is_some_and(|d| d >= now) {
+                    position += 1;
+                    continue;
+                }
+                if let Some(hashes) = self.expiry_buckets.get_mut(&bucket) {
+                    hashes.swap_remove(position);
+                }
+                if let Some(deadline) =
+                    deadline.filter(|_| !self.execution_logs.contains_key(&hash))
+                {
+                    expired.push((hash, deadline));
+                }
+            }
+            let drained = self
+                .expiry_buckets
+                .get(&bucket)
+                .is_none_or(|hashes| hashes.is_empty());
+            if bucket == now_bucket || !drained {
+                break;
+            }
+            self.expiry_buckets.remove(&bucket);
+            self.expiry_cursor += 1;
+            scanned += 1;
+        }
+
+        for (intent_hash, deadline) in &expired {
+            let market = self.event_market(intent_hash);
+            self.unindex_swept_intent(intent_hash);
+            self.intent_metadata.remove(intent_hash);
+            self.private_intents.remove(intent_hash);
+            if let Some(json) = self.canonical_intents.remove(intent_hash) {
+                self.canonical_hashes
+                    .remove(&Canonicalizer::compute_hash(&json));
+            }
+            self.hash_versions.remove(intent_hash);
+            self.intent_deadlines.remove(intent_hash);
+            self.release_deadline(*deadline);
+            self.intent_webhooks.remove(intent_hash);
+            self.clear_dead_letter(intent_hash);
+            self.expired_intents.insert(intent_hash.clone(), now);
+            // Cancelled intents are swept for storage but stay cancelled
+            if self.intent_status(intent_hash) != Some(IntentStatus::Cancelled) {
+                self.set_intent_status(intent_hash, IntentStatus::Expired);
+            }
+            self.record_timeline(intent_hash, TimelineEvent::Expired, None);
+            let topic = EventEmitter::emit_intent_expired(intent_hash.clone(), *deadline, &market);
+            self.record_topic(topic);
+        }
+        self.intent_metadata_keys
+            .retain(|hash| !expired.iter().any(|(swept, _)| swept == hash));
+        if !expired.is_empty() {
+            log!("Swept {} expired intents", expired.len());
+        }
+        let reclaimed = self.reclaim_idempotency_records(limit);
+        if reclaimed > 0 {
+            log!("Reclaimed {} idempotency records", reclaimed);
+        }
+        expired.len() as u32
+    }
+
+    /// One-call monitoring summary: dead letters awaiting retry, unexecuted
+    /// intents due in an hour that has ended that no sweep has removed yet,
+    /// and storage headroom of the contract balance. Overall status is the
+    /// worst component. Both counts come from running counters; the overdue
+    /// count reads at most MAX_SWEEP_SCAN unswept expiry hours
+    pub fn get_health(&self) -> ContractHealth {
+        let unretried = self.unretried_dead_letters;
+        let dead_letter_status = match unretried {
+            0 => HealthStatus::Green,
+            n if n < HEALTH_DEAD_LETTER_RED => HealthStatus::Yellow,
+            _ => HealthStatus::Red,
+        };
+
+        let now_bucket = env::block_timestamp() / EXPIRY_BUCKET_NS;
+        let overdue: u64 = (self.expiry_cursor..now_bucket)
+            .take(MAX_SWEEP_SCAN as usize)
+            .filter_map(|bucket| self.expiry_bucket_counts.get(&bucket))
+            .sum();
+        let overdue_status = match overdue {
+            0 => HealthStatus::Green,
+            n if n < HEALTH_OVERDUE_RED => HealthStatus::Yellow,
+            _ => HealthStatus::Red,
+        };
+
+        let locked = env::storage_usage() as u128 * env::storage_byte_cost().as_yoctonear();
+        let headroom = env::account_balance().as_yoctonear().saturating_sub(locked);
+        let storage_status = if headroom < HEALTH_STORAGE_RED_YOCTO {
+            HealthStatus::Red
+        } else if headroom < HEALTH_STORAGE_YELLOW_YOCTO {
+            HealthStatus::Yellow
+        } else {
+            HealthStatus::Green
+        };
+
+        let components = vec![
+            HealthComponent {
+                component: "dead_letter_queue".to_string(),
+                status: dead_letter_status,
+                detail: format!("{} awaiting retry", unretried),
+            },
+            HealthComponent {
+                component: "expiry_sweep".to_string(),
+                status: overdue_status,
+                detail: format!("{} intents past deadline", overdue),
+            },
+            HealthComponent {
+                component: "storage".to_string(),
+                status: storage_status,
+                detail: format!(
+                    "{} bytes used, {} NEAR headroom",
+                    env::storage_usage(),
+                    format_units(headroom, 24)
+                ),
+            },
+        ];
+        ContractHealth {
+            status: components
+                .iter()
+                .map(|c| c.status)
+                .max()
+                .unwrap_or(HealthStatus::Green),
+            components,
+        }
+    }
+
+    /// Lifecycle state of an intent; None if the hash is unknown
+    pub fn get_intent_status(&self, intent_hash: String) -> Option<IntentStatus> {
+        self.intent_status(&intent_hash)
+    }
+
+    /// Compact status for cross-contract callers, see IntentStatusCode
+    pub fn get_intent_status_code(&self, intent_hash: String) -> u8 {
+        let code = match self.intent_status(&intent_hash) {
+            None => IntentStatusCode::Unknown,
+            Some(IntentStatus::Committed) => IntentStatusCode::Committed,
+            Some(IntentStatus::Submitted) => IntentStatusCode::Pending,
+            Some(IntentStatus::Executed) => IntentStatusCode::Executed,
+            Some(IntentStatus::Cancelled) => IntentStatusCode::Cancelled,
+            Some(IntentStatus::Failed) => IntentStatusCode::Failed,
+            Some(IntentStatus::Expired) => IntentStatusCode::Expired,
+        };
+        code as u8
+    }
+
+    /// Intent hashes stored with trace_id, in storage order; cursor is a
+    /// position in the trace's list. Swept intents are skipped, so a page may
+    /// hold fewer than limit hashes
+    pub fn get_intents_by_trace_id(
+        &self,
+        trace_id: String,
+        cursor: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<String> {
+        let limit = limit
+            .unwrap_or(MAX_TRACE_INTENTS_PAGE)
+            .min(MAX_TRACE_INTENTS_PAGE) as usize;
+        match self.trace_intents.get(&trace_id) {
+            Some(hashes) => hashes
+                .iter()
+                .skip(cursor.unwrap_or(0) as usize)
+                .take(limit)
+                .filter(|hash| !self.expired_intents.contains_key(*hash))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_intent_metadata(&self, intent_hash: String) -> Option<IntentMetadata> {
+        self.intent_metadata.get(&intent_hash).cloned()
+    }
+
+    /// Stored intents a solver can execute now, one page of the pending
+    /// index at a time, soonest deadline first within the page. cursor is a
+    /// position in the index; intents no longer pending, expired, untriggered
+    /// or dormant bracket children are skipped, as are those the filter
+    /// excludes, so a page may hold fewer than limit intents
+    pub fn get_pending_intents(&self, filter: PendingIntentFilter) -> Vec<PendingIntentSummary> {
+        let limit = filter
+            .limit
+            .unwrap_or(MAX_PENDING_INTENTS_PAGE)
+            .min(MAX_PENDING_INTENTS_PAGE) as usize;
+        let min_size = filter.min_size.as_ref().map(|size| {
+            size.parse::<f64>()
+                .unwrap_or_else(|_| env::panic_str(&format!("Invalid min_size: {}", size)))
+        });
+        let now = env::block_timestamp();
+
+        let mut pending: Vec<(u64, PendingIntentSummary)> = self
+            .pending_intent_keys
+            .iter()
+            .skip(filter.cursor.unwrap_or(0) as usize)
+            .take(limit)
+            .filter(|hash| self.pending_intents.contains(*hash))
+            .filter(|hash| !self.execution_logs.contains_key(*hash))
+            .filter(|hash| {
+                self.bracket_parents
+                    .get(*hash)
+                    .is_none_or(|parent| self.execution_logs.contains_key(parent))
+            })
+            .filter_map(|hash| self.intent_metadata.get(hash))
+            .filter(|metadata| metadata.cancelled_at.is_none())
+            .filter(|metadata| metadata.trigger.is_none() || metadata.triggered_at.is_some())
+            .filter_map(|metadata| {
+                let deadline = self
+                    .intent_deadlines
+                    .get(&metadata.intent_hash)
+                    .copied()
+                    .unwrap_or(u64::MAX);
+                (deadline >= now).then_some((deadline, metadata))
+            })
+            .filter(|(_, metadata)| {
+                filter.symbol.as_ref().is_none_or(|s| *s == metadata.symbol)
+                    && filter
+                        .instrument
+                        .as_ref()
+                        .is_none_or(|i| *i == metadata.instrument)
+                    && filter
+                        .solver_id
+                        .as_ref()
+                        .is_none_or(|s| *s == metadata.solver_id)
+                    && filter
+                        .tag
+                        .as_ref()
+                        .is_none_or(|t| metadata.tags.contains(t))
+                    && min_size.is_none_or(|min| {
+                        metadata.size.parse::<f64>().is_ok_and(|size| size >= min)
+                    })
+            })
+            .map(|(deadline, metadata)| {
+                (
+                    deadline,
+                    PendingIntentSummary {
+                        intent_hash: metadata.intent_hash.clone(),
+                        solver_id: metadata.solver_id.clone(),
+                        instrument: metadata.instrument.clone(),
+                        symbol: metadata.symbol.clone(),
+                        side: metadata.side.clone(),
+                        size: metadata.size.clone(),
+                        timestamp: metadata.timestamp,
+                    },
+                )
+            })
+            .collect();
+
+        // Intents without a recorded deadline sort last, then oldest first
+        pending.sort_by(|(a_deadline, a), (b_deadline, b)| {
+            a_deadline
+                .cmp(b_deadline)
+                .then_with(|| a.timestamp.cmp(&b.timestamp))
+                .then_with(|| a.intent_hash.cmp(&b.intent_hash))
+        });
+        pending
+            .into_iter()
+            .map(|(_, summary)| summary)
+            .collect()
+    }
+
+    /// One log per intent, submitted by the authorized solver named in it;
+    /// later changes go through propose_log_correction. Retries carrying the
+    /// same idempotency_key within the TTL return the original result
+    /// without touching state; past the TTL the key's record is dropped and
+    /// the key can be used again
+    pub fn log_execution(
+        &mut self,
+        intent_hash: String,
+        log: ExecutionLog,
+        idempotency_key: Option<String>,
+    ) -> String {
+        let record_key =
+            idempotency_key.map(|key| format!("{}:{}", env::predecessor_account_id(), key));
+        if let Some(key) = &record_key {
+            let ttl = self.parameter("idempotency_key_ttl_ns");
+            match self.idempotency_records.get(key) {
+                Some(record) if env::block_timestamp() <= record.recorded_at + ttl => {
+                    require!(
+                        record.intent_hash == intent_hash,
+                        "Idempotency key already used for a different intent"
+                    );
+                    log!("Replayed V2 execution log for intent: {}", intent_hash);
+                    return record.result.clone();
+                }
+                Some(_) => {
+                    self.idempotency_records.remove(key);
+                }
+                None => {}
+            }
+        }
+
+        let solver_id = env::predecessor_account_id();
+        require!(
+            self.authorized_solvers.contains(&solver_id),
+            "Only authorized solvers can log executions"
+        );
+        require!(
+            log.solver_id == solver_id.as_str(),
+            "Execution log solver_id must be the caller"
+        );
+        require!(
+            !self.execution_logs.contains_key(&intent_hash),
+            "Execution already logged; use propose_log_correction to amend it"
+        );
+        require!(
+            !self.private_intents.contains_key(&intent_hash),
+            "Private intent must be revealed before execution"
+        );
+        self.check_bracket_execution(&intent_hash);
+        require!(
+            self.intent_metadata
+                .get(&intent_hash)
+                .is_none_or(|metadata| metadata.cancelled_at.is_none()),
+            "Intent has been cancelled"
+        );
+        require!(
+            !self.expired_intents.contains_key(&intent_hash)
+                && self
+                    .intent_deadlines
+                    .get(&intent_hash)
+                    .is_none_or(|deadline| env::block_timestamp() <= *deadline),
+            "Intent deadline has passed"
+        );
+        require!(
+            self.intent_metadata
+                .get(&intent_hash)
+                .is_none_or(|metadata| {
+                    metadata.trigger.is_none() || metadata.triggered_at.is_some()
+                }),
+            "Trigger condition has not been met"
+        );
+        self.check_quote_reference(&intent_hash, &log);
+        log!(
+            "Logging V2 execution for intent: {}{}",
+            intent_hash,
+            self.trace_suffix(&intent_hash)
+        );
+        self.execution_log_keys.push(intent_hash.clone());
+        self.record_symbol_stats(&intent_hash, &log.notional);
+        self.record_fill_source(&log.solver_id, &log.fill_source);
+        self.notify_webhook(&intent_hash, &log);
+        let message = format!(
+            "Logged V2 execution {} at venue {} with status {}",
+            intent_hash, log.venue, log.status
+        );
+        self.index_execution_venue(&intent_hash, &log.venue);
+        self.set_intent_status(&intent_hash, IntentStatus::Executed);
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Executed,
+            Some(format!("{} at {}", log.status, log.venue)),
+        );
+        let market = self.event_market(&intent_hash);
+        let topic = EventEmitter::emit_execution_logged(
+            intent_hash.clone(),
+            solver_id,
+            log.venue.clone(),
+            log.fill_price.clone(),
+            log.notional.clone(),
+            log.status.clone(),
+            &market,
+        );
+        self.record_topic(topic);
+        self.resolve_bracket(&intent_hash);
+        self.execution_logs.insert(intent_hash.clone(), log);
+        self.clear_dead_letter(&intent_hash);
+        if let Some(deadline) = self.intent_deadlines.get(&intent_hash).copied() {
+            self.release_deadline(deadline);
+        }
+
+        if let Some(key) = record_key {
+            self.idempotency_queue.insert(
+                self.idempotency_queue_tail,
+                (key.clone(), env::block_timestamp()),
+            );
+            self.idempotency_queue_tail += 1;
+            self.idempotency_records.insert(
+                key,
+                IdempotencyRecord {
+                    intent_hash,
+                    result: message.clone(),
+                    recorded_at: env::block_timestamp(),
+                },
+            );
+        }
+        message
+    }
+
+    /// Record a failed execution attempt; the intent stays pending and the
+    /// entry is cleared once an execution is logged
+    pub fn report_failed_execution(&mut self, intent_hash: String, error_code: String) -> u32 {
+        let solver_id = env::predecessor_account_id();
+        require!(
+            self.authorized_solvers.contains(&solver_id),
+            "Only authorized solvers can report failed executions"
+        );
+        require!(!error_code.is_empty(), "Error code cannot be empty");
+        self.set_intent_status(&intent_hash, IntentStatus::Failed);
+
+        let attempts = match self.dead_letters.get_mut(&intent_hash) {
+            Some(entry) => {
+                entry.solver_id = solver_id.to_string();
+                entry.error_code = error_code.clone();
+                entry.attempts += 1;
+                entry.last_attempt_at = env::block_timestamp();
+                if entry.retry_requested {
+                    entry.retry_requested = false;
+                    self.unretried_dead_letters += 1;
+                }
+                entry.attempts
+            }
+            None => {
+                self.unretried_dead_letters += 1;
+                self.dead_letter_keys.push(intent_hash.clone());
+                self.dead_letters.insert(
+                    intent_hash.clone(),
+                    DeadLetterEntry {
+                        intent_hash: intent_hash.clone(),
+                        solver_id: solver_id.to_string(),
+                        error_code: error_code.clone(),
+                        attempts: 1,
+                        last_attempt_at: env::block_timestamp(),
+                        retry_requested: false,
+                    },
+                );
+                1
+            }
+        };
+        log!(
+            "Execution of {} failed with {} (attempt {}){}",
+            intent_hash,
+            error_code,
+            attempts,
+            self.trace_suffix(&intent_hash)
+        );
+        attempts
+    }
+
+    /// Flag a dead-lettered intent for another attempt, up to max_execution_attempts
+    pub fn retry_failed_execution(&mut self, intent_hash: String) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.treasury_account_id || self.authorized_solvers.contains(&caller),
+            "Only treasury or authorized solvers can retry executions"
+        );
+        let max_attempts = self.parameter("max_execution_attempts");
+        let entry = self
+            .dead_letters
+            .get_mut(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("Intent is not in the dead-letter queue"));
+        require!(!entry.retry_requested, "Retry already requested");
+        require!(
+            (entry.attempts as u64) < max_attempts,
+            "Maximum execution attempts reached"
+        );
+        entry.retry_requested = true;
+        self.unretried_dead_letters -= 1;
+        self.set_intent_status(&intent_hash, IntentStatus::Submitted);
+        log!(
+            "Retry requested for {} by {}{}",
+            intent_hash,
+            caller,
+            self.trace_suffix(&intent_hash)
+        );
+    }
+
+    /// Dead-lettered intents in the order they first failed. cursor is a
+    /// position in the queue index; cleared entries are skipped, so a page
+    /// may hold fewer than limit entries
+    pub fn get_dead_letter_queue(
+        &self,
+        cursor: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<DeadLetterEntry> {
+        let limit = limit
+            .unwrap_or(MAX_DEAD_LETTER_PAGE)
+            .min(MAX_DEAD_LETTER_PAGE) as usize;
+        self.dead_letter_keys
+            .iter()
+            .skip(cursor.unwrap_or(0) as usize)
+            .take(limit)
+            .filter_map(|hash| self.dead_letters.get(hash).cloned())
+            .collect()
+    }
+
+    /// Stored intents signed by the account as FIX-style execution reports,
+    /// in storage order; cursor is a position in the account's intent list.
+    /// Swept intents are skipped, so a page may hold fewer than limit reports
+    pub fn export_account_activity(
+        &self,
+        account: String,
+        tag: Option<String>,
+        cursor: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<FixExecutionReport> {
+        let limit = limit
+            .unwrap_or(MAX_ACCOUNT_ACTIVITY_PAGE)
+            .min(MAX_ACCOUNT_ACTIVITY_PAGE) as usize;
+        let Some(hashes) = self.account_intents.get(&account) else {
+            return Vec::new();
+        };
+        hashes
+            .iter()
+            .skip(cursor.unwrap_or(0) as usize)
+            .take(limit)
+            .filter_map(|hash| {
+                let metadata = self
+                    .intent_metadata
+                    .get(hash)
+                    .filter(|metadata| tag.as_ref().is_none_or(|t| metadata.tags.contains(t)))?;
+                let side = match metadata.side.to_lowercase().as_str() {
+                    "long" | "buy" => "1",
+                    "short" | "sell" => "2",
+                    other => env::panic_str(&format!("Unknown side {} for {}", other, hash)),
+                };
+                let report = FixExecutionReport {
+                    cl_ord_id: hash.clone(),
+                    symbol: metadata.symbol.clone(),
+                    side: side.to_string(),
+                    order_qty: metadata.size.clone(),
+                    exec_type: "0".to_string(),
+                    last_px: None,
+                    last_qty: None,
+                    cum_qty: "0".to_string(),
+                    last_mkt: None,
+                    transact_time: metadata.timestamp,
+                };
+                Some(match self.get_effective_execution_log(hash.clone()) {
+                    Some(log) => FixExecutionReport {
+                        exec_type: "F".to_string(),
+                        last_px: Some(log.fill_price),
+                        last_qty: Some(metadata.size.clone()),
+                        cum_qty: metadata.size.clone(),
+                        last_mkt: Some(log.venue),
+                        transact_time: log.timestamp,
+                        ..report
+                    },
+                    None => report,
+                })
+            })
+            .collect()
+    }
+
+    /// Wallet-ready encoding of a stored intent's canonical JSON. The NEP-413
+    /// nonce is the 32-byte intent hash, so one signature binds one intent.
+    pub fn get_signing_payload(
+        &self,
+        intent_hash: String,
+        format: SigningPayloadFormat,
+    ) -> Option<SigningPayload> {
+        let canonical_json = self.canonical_intents.get(&intent_hash)?;
+        let payload = match format {
+            SigningPayloadFormat::Nep413 => {
+                let nonce = hex::decode(&intent_hash)
+                    .map(|bytes| base64_encode(&bytes, false))
+                    .unwrap_or_else(|_| env::panic_str("Stored intent hash is not hex"));
+                let recipient = env::current_account_id();
+                serde_json::json!({
+                    "message": canonical_json,
+                    "nonce": nonce,
+                    "recipient": recipient,
+                })
+                .to_string()
+            }
+            SigningPayloadFormat::DeepLink => {
+                let blob = serde_json::json!({
+                    "intent_hash": intent_hash,
+                    "intent": canonical_json,
+                })
+                .to_string();
+                base64_encode(blob.as_bytes(), true)
+            }
+        };
+        Some(SigningPayload {
+            intent_hash,
+            format,
+            payload,
+        })
+    }
+
+    /// Every recorded lifecycle step for the intent, oldest first
+    pub fn get_intent_timeline(&self, intent_hash: String) -> Vec<TimelineEntry> {
+        self.intent_timelines
+            .get(&intent_hash)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Original execution log as submitted by the solver
+    pub fn get_execution_log(&self, intent_hash: String) -> Option<ExecutionLog> {
+        self.execution_logs.get(&intent_hash).cloned()
+    }
+
+    /// Executions logged at a venue with timestamp in [from_ts, to_ts].
+    /// cursor is a position in the venue index, not a count of results.
+    pub fn get_executions_by_venue(
+        &self,
+        venue_id: String,
+        from_ts: Option<u64>,
+        to_ts: Option<u64>,
+        cursor: Option<u64>,
+        limit: Option<u32>,
+    ) -> VenueExecutionsPage {
+        let limit = limit
+            .unwrap_or(MAX_VENUE_EXECUTIONS_PAGE)
+            .min(MAX_VENUE_EXECUTIONS_PAGE) as usize;
+        let hashes = match self.executions_by_venue.get(&venue_id) {
+            Some(hashes) => hashes,
+            None => {
+                return VenueExecutionsPage {
+                    executions: Vec::new(),
+                    next_cursor: None,
+                }
+            }
+        };
+
+        let mut executions = Vec::new();
+        let mut position = cursor.unwrap_or(0).min(hashes.len() as u64) as u32;
+        while position < hashes.len() && executions.len() < limit {
+            if let Some(log) = hashes
+                .get(position)
+                .and_then(|hash| self.execution_logs.get(hash))
+            {
+                if from_ts.is_none_or(|from| log.timestamp >= from)
+                    && to_ts.is_none_or(|to| log.timestamp <= to)
+                {
+                    executions.push(ExecutionSummary {
+                        intent_hash: log.intent_hash.clone(),
+                        solver_id: log.solver_id.clone(),
+                        fill_price: log.fill_price.clone(),
+                        notional: log.notional.clone(),
+                        status: log.status.clone(),
+                        timestamp: log.timestamp,
+                    });
+                }
+            }
+            position += 1;
+        }
+
+        VenueExecutionsPage {
+            executions,
+            next_cursor: (position < hashes.len()).then_some(position as u64),
+        }
+    }
+
+    /// Execution log with the latest approved correction applied
+    pub fn get_effective_execution_log(&self, intent_hash: String) -> Option<ExecutionLog> {
+        let approved = self.latest_approved_correction(&intent_hash);
+        match approved {
+            Some(correction) => Some(correction.corrected_log),
+            None => self.get_execution_log(intent_hash),
+        }
+    }
+
+    /// Ordered by correction_id, i.e. proposal order
+    pub fn get_log_corrections(&self, intent_hash: String) -> Vec<LogCorrection> {
+        self.log_corrections
+            .get(&intent_hash)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Solver proposes a corrected log; the original stays untouched until review
+    pub fn propose_log_correction(
+        &mut self,
+        intent_hash: String,
+        corrected_log: ExecutionLog,
+        evidence_uri: String,
+    ) -> u32 {
+        let solver_id = env::predecessor_account_id();
+        let original = self
+            .execution_logs
+            .get(&intent_hash)
+            .unwrap_or_else(|| env::panic_str("No execution log for intent"));
+        require!(
+            original.solver_id == solver_id.as_str(),
+            "Only the logging solver can propose a correction"
+        );
+        require!(
+            corrected_log.intent_hash == intent_hash,
+            "Corrected log must reference the same intent"
+        );
+        require!(!evidence_uri.is_empty(), "Evidence URI cannot be empty");
+
+        let supersedes = self
+            .latest_approved_correction(&intent_hash)
+            .map(|correction| correction.correction_id);
+        let mut corrections = self.get_log_corrections(intent_hash.clone());
+        require!(
+            !corrections
+                .iter()
+                .any(|c| c.status == CorrectionStatus::Pending && !self.review_window_elapsed(c)),
+            "A correction is already pending review"
+        );
+
+        let correction_id = corrections.len() as u32;
+        corrections.push(LogCorrection {
+            correction_id,
+            intent_hash: intent_hash.clone(),
+            corrected_log,
+            evidence_uri: evidence_uri.clone(),
+            proposed_by: solver_id.to_string(),
+            proposed_at: env::block_timestamp(),
+            supersedes,
+            status: CorrectionStatus::Pending,
+            reviewed_by: None,
+            reviewed_at: None,
+        });
+        self.log_corrections
+            .insert(intent_hash.clone(), corrections);
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::CorrectionProposed,
+            Some(format!("correction {}", correction_id)),
+        );
+
+        let market = self.event_market(&intent_hash);
+        let topic = EventEmitter::emit_log_correction_proposed(
+            intent_hash,
+            correction_id,
+            solver_id,
+            evidence_uri,
+            supersedes,
+            &market,
+        );
+        self.record_topic(topic);
+        correction_id
+    }
+
+    pub fn approve_log_correction(&mut self, intent_hash: String, correction_id: u32) {
+        self.review_log_correction(intent_hash, correction_id, true);
+    }
+
+    pub fn reject_log_correction(&mut self, intent_hash: String, correction_id: u32) {
+        self.review_log_correction(intent_hash, correction_id, false);
+    }
+
+    pub fn set_settlement_preference(&mut self, token: String, chain: String) {
+        let account_id = env::predecessor_account_id();
+        let preference = Collateral {
+            token: token.trim().to_string(),
+            chain: chain.trim().to_lowercase(),
+        };
+        require!(
+            !preference.token.is_empty(),
+            "Settlement token cannot be empty"
+        );
+        require!(
+            !preference.chain.is_empty(),
+            "Settlement chain cannot be empty"
+        );
+        log!(
+            "Settlement preference for {}: {} on {}",
+            account_id,
+            preference.token,
+            preference.chain
+        );
+        self.settlement_preferences.insert(account_id, preference);
+    }
+
+    pub fn clear_settlement_preference(&mut self) {
+        let account_id = env::predecessor_account_id();
+        if self.settlement_preferences.remove(&account_id).is_some() {
+            log!("Cleared settlement preference for {}", account_id);
+        }
+    }
+
+    /// Solvers read this when preparing TokenDiffs; None means pay in collateral
+    pub fn get_settlement_preference(&self, account_id: AccountId) -> Option<Collateral> {
+        self.settlement_preferences.get(&account_id).cloned()
+    }
+
+    /// Register a golden vector; it must hash as claimed under the live canonicalizer
+    pub fn add_test_vector(&mut self, version: String, vector: TestVector, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add test vectors"
+        );
+        Self::check_admin_reason(&reason);
+        let canonical =
+            Self::canonical_intent(&vector.input).unwrap_or_else(|e| env::panic_str(&e));
+        require!(
+            canonical.hash_version_of(&vector.expected_hash).is_some(),
+            format!(
+                "Test vector {} hashes to {}, not {}",
+                vector.name,
+                canonical.hash(HASH_VERSION_LEGACY),
+                vector.expected_hash
+            )
+        );
+
+        let mut vectors = self.get_test_vectors(version.clone());
+        require!(
+            !vectors.iter().any(|v| v.name == vector.name),
+            "Test vector name already registered"
+        );
+        log!(
+            "Added test vector {} for version {}: {}",
+            vector.name,
+            version,
+            reason
+        );
+        self.record_admin_action(
+            "add_test_vector",
+            format!("{}/{}", version, vector.name),
+            reason,
+        );
+        let position = vectors.partition_point(|v| v.name < vector.name);
+        vectors.insert(position, vector);
+        self.test_vectors.insert(version, vectors);
+    }
+
+    /// Sorted by vector name
+    pub fn get_test_vectors(&self, version: String) -> Vec<TestVector> {
+        self.test_vectors.get(&version).cloned().unwrap_or_default()
+    }
+
+    /// CI helper for external SDKs: compare their hash with the contract's
+    pub fn run_vector_check(
+        &self,
+        intent_json: String,
+        expected_hash: String,
+    ) -> VectorCheckResult {
+        match Self::canonical_intent(&intent_json) {
+            Ok(canonical) => {
+                let hash_version = canonical.hash_version_of(&expected_hash);
+                VectorCheckResult {
+                    matches: hash_version.is_some(),
+                    computed_hash: Some(
+                        canonical.hash(hash_version.unwrap_or(HASH_VERSION_LEGACY)),
+                    ),
+                    error: None,
+                    hash_version,
+                }
+            }
+            Err(error) => VectorCheckResult {
+                matches: false,
+                computed_hash: None,
+                error: Some(error),
+                hash_version: None,
+            },
+        }
+    }
+
+    /// 1 = legacy compute_hash, 2 = domain-separated, 3 = JCS; None for unknown hashes
+    pub fn get_intent_hash_version(&self, intent_hash: String) -> Option<u8> {
+        self.hash_versions.get(&intent_hash).copied()
+    }
+
+    /// Permissionless check that intent_json canonicalizes to claimed_hash
+    pub fn verify_intent_hash(
+        &self,
+        intent_json: String,
+        claimed_hash: String,
+    ) -> VectorCheckResult {
+        self.run_vector_check(intent_json, claimed_hash)
+    }
+
+    /// verify_intent_hash over up to MAX_HASH_VERIFY_BATCH entries, results in input order
+    pub fn verify_intent_hashes_batch(
+        &self,
+        entries: Vec<IntentHashClaim>,
+    ) -> Vec<VectorCheckResult> {
+        require!(
+            entries.len() <= MAX_HASH_VERIFY_BATCH,
+            format!("At most {} entries per batch", MAX_HASH_VERIFY_BATCH)
+        );
+        entries
+            .into_iter()
+            .map(|entry| self.run_vector_check(entry.intent_json, entry.claimed_hash))
+            .collect()
+    }
+
+    /// Deterministic edge-case intents for SDK conformance; case N depends only
+    /// on (seed, N). count is capped at MAX_CONFORMANCE_CORPUS
+    pub fn generate_conformance_corpus(&self, seed: u64, count: u32) -> Vec<ConformanceCase> {
+        let count = count.min(MAX_CONFORMANCE_CORPUS) as usize;
+        CorpusGenerator::generate(seed, count)
+            .into_iter()
+            .map(|case| {
+                let (canonical_json, intent_hash, error) =
+                    match Self::canonical_intent(&case.input_json) {
+                        Ok(canonical) => {
+                            let hash = canonical.hash(HASH_VERSION_LEGACY);
+                            (Some(canonical.json), Some(hash), None)
+                        }
+                        Err(e) => (None, None, Some(e)),
+                    };
+                ConformanceCase {
+                    case_id: case.case_id,
+                    mutations: case.mutations,
+                    input_json: case.input_json,
+                    canonical_json,
+                    intent_hash,
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// Stats for executions whose intent metadata was stored; None if no trades
+    pub fn get_symbol_stats(&self, symbol: String) -> Option<SymbolStatsView> {
+        self.symbol_stats
+            .get(&symbol)
+            .map(|stats| Self::symbol_stats_view(symbol.clone(), stats))
+    }
+
+    /// Distribution of a solver's logged fills by source
+    pub fn get_solver_fill_sources(&self, solver_id: String) -> FillSourceCounts {
+        self.solver_fill_sources
+            .get(&solver_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Symbols ranked by 30d notional volume
+    pub fn get_symbol_leaderboard(&self, limit: u32) -> Vec<SymbolStatsView> {
+        let today = env::block_timestamp() / DAY_NS;
+        let mut ranked: Vec<(u128, SymbolStatsView)> = self
+            .stats_symbols
+            .iter()
+            .filter_map(|symbol| {
+                self.symbol_stats.get(symbol).map(|stats| {
+                    (
+                        Self::volume_since(stats, today, 30),
+                        Self::symbol_stats_view(symbol.clone(), stats),
+                    )
+                })
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.symbol.cmp(&b.1.symbol)));
+        ranked
+            .into_iter()
+            .take(limit as usize)
+            .map(|(_, view)| view)
+            .collect()
+    }
+
+    /// Net stored intent sizes per (instrument, symbol); buy nets as long and
+    /// sell as short, in any case. Hashes are deduplicated and sorted first so
+    /// the preview does not depend on input order.
+    pub fn preview_netting(&self, intent_hashes: Vec<String>) -> NettingPreview {
+        let mut hashes = intent_hashes;
+        hashes.sort();
+        hashes.dedup();
+
+        let mut unknown_intents = Vec::new();
+        let mut books: Vec<NettingBook> = Vec::new();
+        for hash in hashes {
+            let Some(metadata) = self.intent_metadata.get(&hash) else {
+                unknown_intents.push(hash);
+                continue;
+            };
+            let size = parse_units(&metadata.size, SIZE_DECIMALS)
+                .unwrap_or_else(|| env::panic_str(&format!("Invalid size for {}", hash)));
+            let index = match books.iter().position(|book| {
+                book.instrument == metadata.instrument && book.symbol == metadata.symbol
+            }) {
+                Some(index) => index,
+                None => {
+                    books.push(NettingBook {
+                        instrument: metadata.instrument.clone(),
+                        symbol: metadata.symbol.clone(),
+                        longs: Vec::new(),
+                        shorts: Vec::new(),
+                    });
+                    books.len() - 1
+                }
+            };
+            match metadata.side.to_lowercase().as_str() {
+                "long" | "buy" => books[index].longs.push((size, hash)),
+                "short" | "sell" => books[index].shorts.push((size, hash)),
+                other => env::panic_str(&format!("Unknown side {} for {}", other, hash)),
+            }
+        }
+
+        let mut residuals = Vec::new();
+        let mut offsetting_intents = Vec::new();
+        for NettingBook {
+            instrument,
+            symbol,
+            longs,
+            mut shorts,
+        } in books
+        {
+            let long_total: u128 = longs.iter().map(|(size, _)| size).sum();
+            let short_total: u128 = shorts.iter().map(|(size, _)| size).sum();
+            for (size, long_hash) in longs {
+                if let Some(position) = shorts.iter().position(|(s, _)| *s == size) {
+                    let (_, short_hash) = shorts.remove(position);
+                    offsetting_intents.push((long_hash, short_hash));
+                }
+            }
+            let (side, size) = match long_total.cmp(&short_total) {
+                std::cmp::Ordering::Greater => ("long", long_total - short_total),
+                std::cmp::Ordering::Less => ("short", short_total - long_total),
+                std::cmp::Ordering::Equal => ("flat", 0),
+            };
+            residuals.push(NetExposure {
+                instrument,
+                symbol,
+                side: side.to_string(),
+                size: format_units(size, SIZE_DECIMALS),
+            });
+        }
+
+        NettingPreview {
+            residuals,
+            offsetting_intents,
+            unknown_intents,
+        }
+    }
+
+    /// Declare the caller's nonce format; applies to intents they sign from now on
+    pub fn set_nonce_policy(&mut self, policy: NoncePolicy) {
+        let account_id = env::predecessor_account_id();
+        log!("Nonce policy for {}: {:?}", account_id, policy);
+        self.nonce_policies.insert(account_id.to_string(), policy);
+    }
+
+    pub fn get_nonce_policy(&self, signer_id: String) -> NoncePolicy {
+        self.nonce_policies
+            .get(&signer_id)
+            .cloned()
+            .unwrap_or(NoncePolicy::Unrestricted)
+    }
+
+    pub fn get_last_nonce(&self, signer_id: String) -> Option<String> {
+        self.last_nonces.get(&signer_id).cloned()
+    }
+
+    /// Lets solvers detect malformed or out-of-order nonces before submitting
+    #[handle_result]
+    pub fn check_nonce(&self, signer_id: String, nonce: String) -> Result<String, String> {
+        let last = self.last_nonces.get(&signer_id);
+        if last == Some(&nonce) {
+            return Err(format!("Nonce {} already used by {}", nonce, signer_id));
+        }
+        match self.get_nonce_policy(signer_id.clone()) {
+            NoncePolicy::Unrestricted => {}
+            NoncePolicy::Uuid => {
+                if !is_uuid_v4(&nonce) {
+                    return Err(format!("Nonce {} is not a UUIDv4", nonce));
+                }
+            }
+            NoncePolicy::Counter => {
+                let value: u64 = nonce
+                    .parse()
+                    .map_err(|_| format!("Nonce {} is not a u64 counter", nonce))?;
+                if let Some(last) = last.and_then(|l| l.parse::<u64>().ok()) {
+                    if value <= last {
+                        return Err(format!("Nonce {} is not greater than {}", value, last));
+                    }
+                }
+            }
+            NoncePolicy::HashChain => {
+                if let Some(last) = last {
+                    let expected = hex::encode(env::sha256(last.as_bytes()));
+                    if nonce.to_lowercase() != expected {
+                        return Err(format!(
+                            "Nonce {} does not extend hash chain from {}",
+                            nonce, last
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(format!("Nonce {} accepted for {}", nonce, signer_id))
+    }
+
+    pub fn add_policy_rule(
+        &mut self,
+        description: String,
+        scope: Option<String>,
+        conditions: Vec<RuleCondition>,
+        reason: String,
+    ) -> u32 {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can add policy rules"
+        );
+        Self::check_admin_reason(&reason);
+        require!(
+            !conditions.is_empty(),
+            "Policy rule needs at least one condition"
+        );
+        for condition in &conditions {
+            if let Err(e) = Self::check_rule_condition(condition) {
+                env::panic_str(&e);
+            }
+        }
+
+        let rule_id = self.next_policy_rule_id;
+        self.next_policy_rule_id += 1;
+        self.policy_rules.push(PolicyRule {
+            rule_id,
+            description,
+            scope,
+            conditions,
+            created_by: env::predecessor_account_id().to_string(),
+            created_at: env::block_timestamp(),
+        });
+        log!("Added policy rule {}: {}", rule_id, reason);
+        self.record_admin_action("add_policy_rule", rule_id.to_string(), reason);
+        rule_id
+    }
+
+    pub fn remove_policy_rule(&mut self, rule_id: u32, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can remove policy rules"
+        );
+        Self::check_admin_reason(&reason);
+        let before = self.policy_rules.len();
+        self.policy_rules.retain(|rule| rule.rule_id != rule_id);
+        require!(self.policy_rules.len() < before, "Policy rule not found");
+        log!("Removed policy rule {}: {}", rule_id, reason);
+        self.record_admin_action("remove_policy_rule", rule_id.to_string(), reason);
+    }
+
+    /// Sorted by rule_id, which only increases
+    pub fn get_policy_rules(&self) -> Vec<PolicyRule> {
+        self.policy_rules.clone()
+    }
+
+    /// Intents the rule has kept out of store_intent_metadata; removed rules
+    /// keep their count
+    pub fn get_policy_rule_hits(&self, rule_id: u32) -> u64 {
+        self.policy_rule_hits.get(&rule_id).copied().unwrap_or(0)
+    }
+
+    pub fn add_intent_tag(&mut self, tag: String, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can manage intent tags"
+        );
+        Self::check_admin_reason(&reason);
+        require!(
+            !tag.is_empty()
+                && tag
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            "Tags must be lowercase ascii, digits or '_'"
+        );
+        if insert_sorted(&mut self.tag_taxonomy, tag.clone()) {
+            log!("Added intent tag: {} ({})", tag, reason);
+            self.record_admin_action("add_intent_tag", tag, reason);
+        }
+    }
+
+    /// Already stored intents keep the tag and its counter
+    pub fn remove_intent_tag(&mut self, tag: String, reason: String) {
+        require!(
+            env::predecessor_account_id() == self.treasury_account_id,
+            "Only treasury can manage intent tags"
+        );
+        Self::check_admin_reason(&reason);
+        let before = self.tag_taxonomy.len();
+        self.tag_taxonomy.retain(|t| *t != tag);
+        if self.tag_taxonomy.len() < before {
+            log!("Removed intent tag: {} ({})", tag, reason);
+            self.record_admin_action("remove_intent_tag", tag, reason);
+        }
+    }
+
+    /// Treasury actions in the order they were taken; cursor is an action_id
+    pub fn get_admin_actions(&self, cursor: Option<u64>, limit: Option<u32>) -> Vec<AdminAction> {
+        let limit = limit
+            .unwrap_or(MAX_ADMIN_ACTIONS_PAGE)
+            .min(MAX_ADMIN_ACTIONS_PAGE) as usize;
+        self.admin_actions
+            .iter()
+            .skip(cursor.unwrap_or(0) as usize)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Taxonomy tags, sorted, with the number of stored intents carrying each
+    pub fn get_intent_tags(&self) -> Vec<TagCount> {
+        self.tag_taxonomy
+            .iter()
+            .map(|tag| TagCount {
+                tag: tag.clone(),
+                intent_count: self.tag_counts.get(tag).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Topics that have had at least one event, sorted; see events.rs for the format
+    pub fn get_topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.topics.iter().cloned().collect();
+        topics.sort();
+        topics
+    }
+
+    /// Constraint defaults and bounds the canonicalizer applies for a schema
+    /// version; None for versions it does not accept
+    pub fn get_constraint_defaults(&self, version: String) -> Option<Vec<ConstraintDefault>> {
+        if version != SCHEMA_VERSION {
+            return None;
+        }
+        let mut defaults: Vec<ConstraintDefault> = INTEGER_CONSTRAINTS
+            .iter()
+            .map(|constraint| ConstraintDefault {
+                field: constraint.field.to_string(),
+                default: serde_json::Value::from(constraint.default),
+                min: Some(0),
+                max: Some(constraint.max),
+            })
+            .collect();
+        // Empty allowlist: any venue
+        defaults.push(ConstraintDefault {
+            field: "venue_allowlist".to_string(),
+            default: serde_json::Value::Array(Vec::new()),
+            min: None,
+            max: None,
+        });
+        Some(defaults)
+    }
+
+    /// JSON Schemas for every NEP-297 event payload, generated from events.rs
+    pub fn get_event_schemas(&self) -> Vec<EventSchema> {
+        EventEmitter::event_schemas()
+            .into_iter()
+            .map(|(event, schema)| EventSchema {
+                event: event.to_string(),
+                schema: serde_json::to_value(schema)
+                    .unwrap_or_else(|e| env::panic_str(&format!("Invalid event schema: {}", e))),
+            })
+            .collect()
+    }
+
+    // V2 Schema validation helper
+    #[handle_result]
+    pub fn validate_v2_intent(&self, intent: DerivativesIntentV2) -> Result<String, String> {
         if intent.version != "1.0.0" {
             return Err(format!(
                 "Invalid version: {}. Must be 1.0.0",
@@ -181,6 +3268,9 @@ impl Contract {
         if intent.derivatives.collateral.chain.is_empty() {
             return Err("Collateral chain cannot be empty".to_string());
         }
+        if let Some(rule) = self.matching_policy_rule(&intent) {
+            return Err(Self::policy_rejection(rule));
+        }
         Ok(format!(
             "V2 Intent validated: {} {} {} on {}",
             intent.derivatives.instrument,
@@ -191,15 +3281,971 @@ impl Contract {
     }
 }
 
+impl Contract {
+    /// Canonical serialization intent hashes are computed over, see canonicalization.rs
+    fn canonical_intent(intent_json: &str) -> Result<CanonicalIntent, String> {
+        let intent: serde_json::Value =
+            serde_json::from_str(intent_json).map_err(|e| format!("Invalid intent JSON: {}", e))?;
+        let canonical = Canonicalizer::canonicalize_intent(&intent)?;
+        let schema_version = canonical["version"]
+            .as_str()
+            .ok_or("Canonical intent has no version")?
+            .to_string();
+        let json = serde_json::to_string(&canonical)
+            .map_err(|e| format!("Failed to serialize canonical intent: {}", e))?;
+        let jcs = Canonicalizer::to_jcs_string(&canonical)?;
+        if jcs != json {
+            log!("Canonical JSON differs from its RFC 8785 serialization");
+        }
+        Ok(CanonicalIntent {
+            value: canonical,
+            json,
+            jcs,
+            schema_version,
+        })
+    }
+
+    fn create_draft(
+        &mut self,
+        intent_json: &str,
+        proposer: AccountId,
+        origin: IntentOrigin,
+    ) -> u64 {
+        let canonical = Self::canonical_intent(intent_json).unwrap_or_else(|e| env::panic_str(&e));
+        let intent_hash = canonical.hash(HASH_VERSION_LEGACY);
+        let intent = canonical.intent().unwrap_or_else(|e| env::panic_str(&e));
+        if origin == IntentOrigin::Contract {
+            require!(
+                intent.signer_id == proposer.as_str(),
+                "Contract-submitted intents must be signed by the calling contract"
+            );
+        }
+        if let Err(e) = self.validate_v2_intent(intent.clone()) {
+            env::panic_str(&e);
+        }
+        if let Err(e) = self.check_nonce(intent.signer_id.clone(), intent.nonce.clone()) {
+            env::panic_str(&e);
+        }
+        self.last_nonces
+            .insert(intent.signer_id.clone(), intent.nonce.clone());
+
+        self.claim_canonical_hash(&canonical, &intent_hash);
+        self.canonical_intents
+            .insert(intent_hash.clone(), canonical.json);
+        self.hash_versions
+            .insert(intent_hash.clone(), HASH_VERSION_LEGACY);
+
+        let draft_id = self.next_draft_id;
+        self.next_draft_id += 1;
+        let now = env::block_timestamp();
+        self.intent_drafts.insert(
+            draft_id,
+            IntentDraft {
+                draft_id,
+                intent_hash: intent_hash.clone(),
+                intent,
+                proposed_by: proposer.to_string(),
+                proposed_at: now,
+                expires_at: now + self.parameter("draft_expiry_ns"),
+                approved_by: None,
+                approved_at: None,
+                status: DraftStatus::Proposed,
+                origin: origin.clone(),
+            },
+        );
+        log!(
+            "Proposed intent draft {} for hash {} by {} (origin: {:?})",
+            draft_id,
+            intent_hash,
+            proposer,
+            origin
+        );
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Proposed,
+            Some(format!("draft {} from {:?}", draft_id, origin)),
+        );
+        draft_id
+    }
+
+    fn insert_intent_metadata(
+        &mut self,
+        intent_hash: String,
+        mut metadata: IntentMetadata,
+    ) -> String {
+        // cancelled_at, triggered_at and the amendment links are contract state: callers
+        // cannot set them or clear them by re-storing. The first solver_id and
+        // trigger are kept
+        let existing = self.intent_metadata.get(&intent_hash);
+        if let Some(existing) = existing {
+            metadata.solver_id = existing.solver_id.clone();
+            metadata.trigger = existing.trigger.clone();
+        }
+        metadata.cancelled_at = existing.and_then(|existing| existing.cancelled_at);
+        metadata.triggered_at = existing.and_then(|existing| existing.triggered_at);
+        metadata.amends = existing.and_then(|existing| existing.amends.clone());
+        metadata.amended_by = existing.and_then(|existing| existing.amended_by.clone());
+        require!(
+            metadata
+                .trace_id
+                .as_ref()
+                .is_none_or(|trace_id| !trace_id.is_empty() && trace_id.len() <= MAX_TRACE_ID_LEN),
+            format!("trace_id must be 1 to {} bytes", MAX_TRACE_ID_LEN)
+        );
+        log!(
+            "Storing V2 intent metadata for hash: {}{}",
+            intent_hash,
+            metadata
+                .trace_id
+                .as_ref()
+                .map(|trace_id| format!(" trace_id={}", trace_id))
+                .unwrap_or_default()
+        );
+        if let Some(trigger) = &metadata.trigger {
+            require!(
+                parse_units(&trigger.trigger_price, PRICE_DECIMALS).is_some_and(|price| price > 0),
+                "trigger_price must be a positive decimal"
+            );
+            require!(!trigger.price_source.is_empty(), "price_source is required");
+        }
+        require!(
+            metadata.tags.len() <= MAX_TAGS_PER_INTENT,
+            format!("At most {} tags per intent", MAX_TAGS_PER_INTENT)
+        );
+        for (i, tag) in metadata.tags.iter().enumerate() {
+            require!(
+                self.tag_taxonomy.binary_search(tag).is_ok(),
+                format!("Unknown intent tag: {}", tag)
+            );
+            require!(!metadata.tags[..i].contains(tag), "Duplicate intent tag");
+        }
+        // Keys migrated from the 1.0.0 layout have no metadata behind them yet
+        if !self.intent_metadata.contains_key(&intent_hash) {
+            if !self.intent_metadata_keys.contains(&intent_hash) {
+                self.intent_metadata_keys.push(intent_hash.clone());
+            }
+            for tag in &metadata.tags {
+                let count = self.tag_counts.get(tag).copied().unwrap_or(0);
+                self.tag_counts.insert(tag.clone(), count + 1);
+            }
+            if let Some(trace_id) = &metadata.trace_id {
+                if !self.trace_intents.contains_key(trace_id) {
+                    let prefix = StorageKey::TraceIntentList {
+                        trace_hash: env::sha256(trace_id.as_bytes()),
+                    };
+                    self.trace_intents
+                        .insert(trace_id.clone(), Vector::new(prefix));
+                }
+                if let Some(hashes) = self.trace_intents.get_mut(trace_id) {
+                    hashes.push(intent_hash.clone());
+                }
+            }
+        }
+        // Re-storing metadata is not a lifecycle change
+        if matches!(
+            self.intent_status(&intent_hash),
+            None | Some(IntentStatus::Committed)
+        ) {
+            self.set_intent_status(&intent_hash, IntentStatus::Submitted);
+        }
+        let message = format!(
+            "Stored V2 intent {} for solver {}",
+            intent_hash, metadata.solver_id
+        );
+        self.record_timeline(
+            &intent_hash,
+            TimelineEvent::Stored,
+            Some(format!("solver {}", metadata.solver_id)),
+        );
+        self.intent_metadata.insert(intent_hash, metadata);
+        message
+    }
+
+    fn check_bracket_execution(&self, intent_hash: &str) {
+        let Some(parent) = self.bracket_parents.get(intent_hash) else {
+            return;
+        };
+        require!(
+            self.execution_logs.contains_key(parent),
+            format!("Bracket parent {} has not filled", parent)
+        );
+        let Some(group) = self.bracket_groups.get(parent) else {
+            return;
+        };
+        if let Some(sibling) = group
+            .children
+            .iter()
+            .find(|child| *child != intent_hash && self.execution_logs.contains_key(*child))
+        {
+            env::panic_str(&format!(
+                "Bracket sibling {} already filled; {} is cancelled",
+                sibling, intent_hash
+            ));
+        }
+    }
+
+    /// A child fill cancels every sibling that can still be cancelled
+    fn resolve_bracket(&mut self, intent_hash: &str) {
+        let Some(parent) = self.bracket_parents.get(intent_hash).cloned() else {
+            return;
+        };
+        let Some(group) = self.bracket_groups.get(&parent).cloned() else {
+            return;
+        };
+        let mut cancelled = Vec::new();
+        for sibling in group.children.iter().filter(|child| *child != intent_hash) {
+            if !self
+                .intent_status(sibling)
+                .is_none_or(|status| status.can_transition_to(IntentStatus::Cancelled))
+            {
+                continue;
+            }
+            if let Some(metadata) = self.intent_metadata.get_mut(sibling) {
+                metadata.cancelled_at = Some(env::block_timestamp());
+            }
+            self.set_intent_status(sibling, IntentStatus::Cancelled);
+            self.record_timeline(
+                sibling,
+                TimelineEvent::Cancelled,
+                Some(format!("bracket {} filled by {}", parent, intent_hash)),
+            );
+            cancelled.push(sibling.clone());
+        }
+        log!(
+            "Bracket {} child {} filled; siblings cancelled",
+            parent,
+            intent_hash
+        );
+        let market = self.event_market(intent_hash);
+        let topic = EventEmitter::emit_bracket_resolved(
+            parent,
+            intent_hash.to_string(),
+            cancelled,
+            &market,
+        );
+        self.record_topic(topic);
+    }
+
+    fn intent_status(&self, intent_hash: &str) -> Option<IntentStatus> {
+        if let Some(status) = self.intent_statuses.get(intent_hash) {
+            return Some(*status);
+        }
+        // Intents stored before statuses were tracked
+        if self.execution_logs.contains_key(intent_hash) {
+            return Some(IntentStatus::Executed);
+        }
+        if self.private_intents.contains_key(intent_hash) {
+            return Some(IntentStatus::Committed);
+        }
+        if self.expired_intents.contains_key(intent_hash) {
+            return Some(IntentStatus::Expired);
+        }
+        let metadata = self.intent_metadata.get(intent_hash)?;
+        Some(if metadata.cancelled_at.is_some() {
+            IntentStatus::Cancelled
+        } else if self
+            .dead_letters
+            .get(intent_hash)
+            .is_some_and(|entry| !entry.retry_requested)
+        {
+            IntentStatus::Failed
+        } else {
+            IntentStatus::Submitted
+        })
+    }
+
+    fn set_intent_status(&mut self, intent_hash: &str, to: IntentStatus) {
+        if let Some(from) = self.intent_status(intent_hash) {
+            require!(
+                from.can_transition_to(to),
+                format!(
+                    "Intent {} cannot move from {:?} to {:?}",
+                    intent_hash, from, to
+                )
+            );
+        }
+        self.intent_statuses.insert(intent_hash.to_string(), to);
+        // The pending index follows the status, so store, execute, cancel
+        // and sweep all keep it current
+        match to {
+            IntentStatus::Submitted => {
+                if self.pending_intents.insert(intent_hash.to_string()) {
+                    self.pending_intent_keys.push(intent_hash.to_string());
+                }
+            }
+            IntentStatus::Executed | IntentStatus::Cancelled | IntentStatus::Expired => {
+                self.pending_intents.remove(intent_hash);
+            }
+            IntentStatus::Committed | IntentStatus::Failed => {}
+        }
+    }
+
+    fn record_deadline(&mut self, intent_hash: &str, deadline: &str) {
+        let deadline_ns = parse_deadline_ns(deadline)
+            .unwrap_or_else(|| env::panic_str(&format!("Invalid intent deadline: {}", deadline)));
+        require!(
+            env::block_timestamp() <= deadline_ns,
+            "Intent deadline has passed"
+        );
+        self.index_deadline(intent_hash, deadline_ns);
+    }
+
+    /// Store the deadline and file the hash under its expiry bucket, once
+    fn index_deadline(&mut self, intent_hash: &str, deadline_ns: u64) {
+        if self
+            .intent_deadlines
+            .insert(intent_hash.to_string(), deadline_ns)
+            .is_some()
+        {
+            return;
+        }
+        let bucket = deadline_ns / EXPIRY_BUCKET_NS;
+        if !self.expiry_buckets.contains_key(&bucket) {
+            self.expiry_buckets
+                .insert(bucket, Vector::new(StorageKey::ExpiryBucket { bucket }));
+        }
+        if let Some(hashes) = self.expiry_buckets.get_mut(&bucket) {
+            hashes.push(intent_hash.to_string());
+        }
+        let count = self.expiry_bucket_counts.get(&bucket).copied().unwrap_or(0);
+        self.expiry_bucket_counts.insert(bucket, count + 1);
+    }
+
+    /// Pop up to limit queued idempotency records older than the TTL, oldest
+    /// first. A key reused after expiry was queued again, so a record is only
+    /// removed if it is the one this entry was queued for
+    fn reclaim_idempotency_records(&mut self, limit: usize) -> usize {
+        let cutoff = env::block_timestamp().saturating_sub(self.parameter("idempotency_key_ttl_ns"));
+        let mut reclaimed = 0;
+        while reclaimed < limit && self.idempotency_queue_head < self.idempotency_queue_tail {
+            let head = self.idempotency_queue_head;
+            let Some((key, recorded_at)) = self.idempotency_queue.get(&head).cloned() else {
+                break;
+            };
+            if recorded_at >= cutoff {
+                break;
+            }
+            if self
+                .idempotency_records
+                .get(&key)
+                .is_some_and(|record| record.recorded_at == recorded_at)
+            {
+                self.idempotency_records.remove(&key);
+            }
+            self.idempotency_queue.remove(&head);
+            self.idempotency_queue_head += 1;
+            reclaimed += 1;
+        }
+        reclaimed
+    }
+
+    /// An intent in the deadline's bucket was executed or swept
+    fn release_deadline(&mut self, deadline_ns: u64) {
+        let bucket = deadline_ns / EXPIRY_BUCKET_NS;
+        match self.expiry_bucket_counts.get(&bucket).copied() {
+            Some(count) if count > 1 => {
+                self.expiry_bucket_counts.insert(bucket, count - 1);
+            }
+            _ => {
+                self.expiry_bucket_counts.remove(&bucket);
+            }
+        }
+    }
+
+    /// Drop the intent's dead letter, if any, keeping the unretried count
+    fn clear_dead_letter(&mut self, intent_hash: &str) {
+        if self
+            .dead_letters
+            .remove(intent_hash)
+            .is_some_and(|entry| !entry.retry_requested)
+        {
+            self.unretried_dead_letters -= 1;
+        }
+    }
+
+    /// Drop a swept intent from the tag and bracket indexes; runs before its
+    /// metadata is removed. Account and trace lists keep the hash so their
+    /// cursors stay stable, and their readers skip swept hashes
+    fn unindex_swept_intent(&mut self, intent_hash: &str) {
+        if let Some(metadata) = self.intent_metadata.get(intent_hash) {
+            for tag in metadata.tags.clone() {
+                let count = self.tag_counts.get(&tag).copied().unwrap_or(0);
+                self.tag_counts.insert(tag, count.saturating_sub(1));
+            }
+        }
+        // A parent that never filled leaves its children nothing to follow
+        if let Some(group) = self.bracket_groups.remove(intent_hash) {
+            for child in &group.children {
+                self.bracket_parents.remove(child);
+            }
+        }
+        if let Some(parent) = self.bracket_parents.remove(intent_hash) {
+            let emptied = self.bracket_groups.get_mut(&parent).is_some_and(|group| {
+                group.children.retain(|child| child != intent_hash);
+                group.children.is_empty()
+            });
+            if emptied {
+                self.bracket_groups.remove(&parent);
+            }
+        }
+    }
+
+    /// Solver and fill time come from the transaction, not the caller's log
+    fn check_quote_reference(&self, intent_hash: &str, log: &ExecutionLog) {
+        let solver_id = env::predecessor_account_id().to_string();
+        if !self.solver_quote_keys.contains_key(&solver_id) {
+            return;
+        }
+        let quote = log
+            .quote_id
+            .as_ref()
+            .and_then(|quote_id| self.signed_quotes.get(quote_id))
+            .unwrap_or_else(|| env::panic_str("Execution must reference a registered quote"));
+        require!(
+            quote.intent_hash == intent_hash && quote.solver_id == solver_id,
+            "Quote was issued for a different intent or solver"
+        );
+        require!(
+            env::block_timestamp() <= quote.expires_at,
+            "Fill is outside the quote validity window"
+        );
+    }
+
+    fn intent_signer(&self, intent_hash: &str) -> Option<String> {
+        if let Some(commitment) = self.private_intents.get(intent_hash) {
+            return Some(commitment.signer_id.clone());
+        }
+        let canonical_json = self.canonical_intents.get(intent_hash)?;
+        let canonical: serde_json::Value = serde_json::from_str(canonical_json).ok()?;
+        canonical["signer_id"].as_str().map(|s| s.to_string())
+    }
+
+    /// Fire-and-forget: the callback runs in its own receipt, so a failing
+    /// target never reverts the execution log. Skipped if consent was revoked
+    /// or the remaining gas cannot cover the budget
+    fn notify_webhook(&self, intent_hash: &str, log: &ExecutionLog) {
+        let Some(target) = self.intent_webhooks.get(intent_hash) else {
+            return;
+        };
+        let consented = self.intent_signer(intent_hash).is_some_and(|signer| {
+            self.webhook_consents
+                .contains_key(&format!("{}:{}", target.contract_id, signer))
+        });
+        let remaining = env::prepaid_gas().saturating_sub(env::used_gas());
+        if !consented || remaining < Gas::from_tgas(target.gas_tgas + WEBHOOK_GAS_RESERVE_TGAS) {
+            log!("Skipped webhook for intent {}", intent_hash);
+            return;
+        }
+        let fill = FillNotification {
+            intent_hash: intent_hash.to_string(),
+            solver_id: log.solver_id.clone(),
+            venue: log.venue.clone(),
+            fill_price: log.fill_price.clone(),
+            notional: log.notional.clone(),
+            status: log.status.clone(),
+            timestamp: log.timestamp,
+        };
+        let args = serde_json::to_vec(&serde_json::json!({ "fill": fill }))
+            .unwrap_or_else(|e| env::panic_str(&format!("Failed to encode webhook args: {}", e)));
+        let contract_id: AccountId = target
+            .contract_id
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("Invalid webhook contract id"));
+        // Bound rather than returned: the call is scheduled when the Promise
+        // drops at the end of this method, and nothing waits on its outcome
+        let _notification = Promise::new(contract_id).function_call(
+            target.method_name.clone(),
+            args,
+            NearToken::from_yoctonear(0),
+            Gas::from_tgas(target.gas_tgas),
+        );
+        log!(
+            "Webhook {}.{} notified for intent {}",
+            target.contract_id,
+            target.method_name,
+            intent_hash
+        );
+    }
+
+    /// Append to the signer's list the first time the hash is seen, i.e.
+    /// before it has a lifecycle status; callers index ahead of setting one
+    fn index_account_intent(&mut self, signer_id: &str, intent_hash: &str) {
+        if self.intent_status(intent_hash).is_some() {
+            return;
+        }
+        if !self.account_intents.contains_key(signer_id) {
+            let prefix = StorageKey::AccountIntentList {
+                account_hash: env::sha256(signer_id.as_bytes()),
+            };
+            self.account_intents
+                .insert(signer_id.to_string(), Vector::new(prefix));
+        }
+        if let Some(hashes) = self.account_intents.get_mut(signer_id) {
+            hashes.push(intent_hash.to_string());
+        }
+    }
+
+    fn event_market(&self, intent_hash: &str) -> EventMarket {
+        match self.intent_metadata.get(intent_hash) {
+            Some(metadata) => EventMarket {
+                symbol: metadata.symbol.clone(),
+                instrument: metadata.instrument.clone(),
+                trace_id: metadata.trace_id.clone(),
+            },
+            None => EventMarket {
+                symbol: "unknown".to_string(),
+                instrument: "unknown".to_string(),
+                trace_id: None,
+            },
+        }
+    }
+
+    /// " trace_id=<id>" for log lines about an intent stored with a trace_id
+    fn trace_suffix(&self, intent_hash: &str) -> String {
+        self.intent_metadata
+            .get(intent_hash)
+            .and_then(|metadata| metadata.trace_id.as_ref())
+            .map(|trace_id| format!(" trace_id={}", trace_id))
+            .unwrap_or_default()
+    }
+
+    fn check_admin_reason(reason: &str) {
+        require!(
+            !reason.trim().is_empty(),
+            "Treasury actions require a reason"
+        );
+        require!(
+            reason.len() <= MAX_ADMIN_REASON_LEN,
+            format!("Reason must be at most {} bytes", MAX_ADMIN_REASON_LEN)
+        );
+    }
+
+    fn record_admin_action(&mut self, action: &'static str, target: String, reason: String) {
+        let action_id = self.admin_actions.len() as u64;
+        let actor = env::predecessor_account_id();
+        let topic = EventEmitter::emit_admin_action(
+            action_id,
+            action,
+            target.clone(),
+            actor.clone(),
+            reason.clone(),
+        );
+        self.record_topic(topic);
+        self.admin_actions.push(AdminAction {
+            action_id,
+            action: action.to_string(),
+            target,
+            actor: actor.to_string(),
+            reason,
+            timestamp: env::block_timestamp(),
+        });
+    }
+
+    /// One intent, one stored hash: the same canonical JSON may not be stored
+    /// again under another hash version
+    fn claim_canonical_hash(&mut self, canonical: &CanonicalIntent, intent_hash: &str) {
+        let key = canonical.hash(HASH_VERSION_LEGACY);
+        if let Some(existing) = self.canonical_hashes.get(&key) {
+            require!(
+                existing == intent_hash,
+                format!("Intent already stored under hash {}", existing)
+            );
+        }
+        self.canonical_hashes.insert(key, intent_hash.to_string());
+    }
+
+    fn from_v1(old: ContractV1, treasury_account_id: AccountId) -> Self {
+        log!(
+            "Migrating {} intent and {} execution keys from {}",
+            old.intent_metadata_keys.len(),
+            old.execution_log_keys.len(),
+            old.version
+        );
+        let mut contract = Self::new(treasury_account_id);
+        contract.version = old.version;
+        for solver_id in old.authorized_solvers {
+            insert_sorted(&mut contract.authorized_solvers, solver_id);
+        }
+        contract.intent_metadata_keys = old.intent_metadata_keys;
+        contract.execution_log_keys = old.execution_log_keys;
+        contract
+    }
+
+    fn record_topic(&mut self, topic: String) {
+        self.topics.insert(topic);
+    }
+
+    fn record_timeline(&mut self, intent_hash: &str, event: TimelineEvent, detail: Option<String>) {
+        let entry = TimelineEntry {
+            event,
+            actor: env::predecessor_account_id().to_string(),
+            timestamp: env::block_timestamp(),
+            detail,
+        };
+        match self.intent_timelines.get_mut(intent_hash) {
+            Some(timeline) => timeline.push(entry),
+            None => {
+                self.intent_timelines
+                    .insert(intent_hash.to_string(), vec![entry]);
+            }
+        }
+    }
+
+    fn rule_field_value<'a>(data: &'a DerivativesData, field: &str) -> Option<&'a str> {
+        match field {
+            "instrument" => Some(&data.instrument),
+            "symbol" => Some(&data.symbol),
+            "side" => Some(&data.side),
+            "leverage" => Some(&data.leverage),
+            "size" => Some(&data.size),
+            "collateral_token" => Some(&data.collateral.token),
+            "collateral_chain" => Some(&data.collateral.chain),
+            _ => None,
+        }
+    }
+
+    fn check_rule_condition(condition: &RuleCondition) -> Result<(), String> {
+        if !POLICY_RULE_FIELDS.contains(&condition.field.as_str()) {
+            return Err(format!("Unknown policy rule field: {}", condition.field));
+        }
+        let numeric_field = NUMERIC_POLICY_RULE_FIELDS.contains(&condition.field.as_str());
+        let ordering = !matches!(condition.operator, RuleOperator::Eq | RuleOperator::Ne);
+        if ordering && !numeric_field {
+            return Err(format!(
+                "Operator {:?} requires a numeric field, got {}",
+                condition.operator, condition.field
+            ));
+        }
+        if numeric_field && condition.value.parse::<f64>().is_err() {
+            return Err(format!("Invalid numeric value: {}", condition.value));
+        }
+        Ok(())
+    }
+
+    fn condition_matches(data: &DerivativesData, condition: &RuleCondition) -> bool {
+        let Some(actual) = Self::rule_field_value(data, &condition.field) else {
+            return false;
+        };
+        if NUMERIC_POLICY_RULE_FIELDS.contains(&condition.field.as_str()) {
+            let (Ok(actual), Ok(expected)) =
+                (actual.parse::<f64>(), condition.value.parse::<f64>())
+            else {
+                return false;
+            };
+            return match condition.operator {
+                RuleOperator::Eq => actual == expected,
+                RuleOperator::Ne => actual != expected,
+                RuleOperator::Gt => actual > expected,
+                RuleOperator::Gte => actual >= expected,
+                RuleOperator::Lt => actual < expected,
+                RuleOperator::Lte => actual <= expected,
+            };
+        }
+        match condition.operator {
+            RuleOperator::Eq => actual == condition.value,
+            RuleOperator::Ne => actual != condition.value,
+            _ => false,
+        }
+    }
+
+    fn matching_policy_rule(&self, intent: &DerivativesIntentV2) -> Option<&PolicyRule> {
+        self.policy_rules.iter().find(|rule| {
+            rule.scope
+                .as_ref()
+                .is_none_or(|symbol| *symbol == intent.derivatives.symbol)
+                && rule
+                    .conditions
+                    .iter()
+                    .all(|condition| Self::condition_matches(&intent.derivatives, condition))
+        })
+    }
+
+    fn policy_rejection(rule: &PolicyRule) -> String {
+        format!(
+            "Rejected by policy rule {} ({}): {}",
+            rule.rule_id,
+            rule.description,
+            rule.conditions
+                .iter()
+                .map(|c| format!("{} {:?} {}", c.field, c.operator, c.value))
+                .collect::<Vec<_>>()
+                .join(" and ")
+        )
+    }
+
+    fn record_fill_source(&mut self, solver_id: &str, fill_source: &FillSource) {
+        let mut counts = self
+            .solver_fill_sources
+            .get(solver_id)
+            .cloned()
+            .unwrap_or_default();
+        match fill_source {
+            FillSource::Venue => counts.venue += 1,
+            FillSource::Internalized => counts.internalized += 1,
+            FillSource::RfqCross => counts.rfq_cross += 1,
+        }
+        self.solver_fill_sources
+            .insert(solver_id.to_string(), counts);
+    }
+
+    fn record_symbol_stats(&mut self, intent_hash: &str, notional: &str) {
+        let Some(symbol) = self
+            .intent_metadata
+            .get(intent_hash)
+            .map(|metadata| metadata.symbol.clone())
+        else {
+            return;
+        };
+        let notional = parse_units(notional, NOTIONAL_DECIMALS).unwrap_or(0);
+        let today = env::block_timestamp() / DAY_NS;
+
+        let mut stats = self.symbol_stats.get(&symbol).cloned().unwrap_or_default();
+        stats.trade_count += 1;
+        stats.total_notional += notional;
+        match stats.daily_notional.last_mut() {
+            Some((day, volume)) if *day == today => *volume += notional,
+            _ => stats.daily_notional.push((today, notional)),
+        }
+        stats
+            .daily_notional
+            .retain(|(day, _)| day + STATS_RETENTION_DAYS > today);
+
+        self.stats_symbols.insert(symbol.clone());
+        self.symbol_stats.insert(symbol, stats);
+    }
+
+    fn volume_since(stats: &SymbolStats, today: u64, days: u64) -> u128 {
+        stats
+            .daily_notional
+            .iter()
+            .filter(|(day, _)| day + days > today)
+            .map(|(_, volume)| volume)
+            .sum()
+    }
+
+    fn symbol_stats_view(symbol: String, stats: &SymbolStats) -> SymbolStatsView {
+        let today = env::block_timestamp() / DAY_NS;
+        SymbolStatsView {
+            symbol,
+            trade_count: stats.trade_count,
+            total_notional: format_units(stats.total_notional, NOTIONAL_DECIMALS),
+            volume_24h: format_units(Self::volume_since(stats, today, 1), NOTIONAL_DECIMALS),
+            volume_7d: format_units(Self::volume_since(stats, today, 7), NOTIONAL_DECIMALS),
+            volume_30d: format_units(Self::volume_since(stats, today, 30), NOTIONAL_DECIMALS),
+        }
+    }
+
+    fn latest_approved_correction(&self, intent_hash: &str) -> Option<LogCorrection> {
+        self.log_corrections
+            .get(intent_hash)
+            .and_then(|corrections| {
+                corrections
+                    .iter()
+                    .rev()
+                    .find(|c| c.status == CorrectionStatus::Approved)
+                    .cloned()
+            })
+    }
+
+    fn index_execution_venue(&mut self, intent_hash: &str, venue: &str) {
+        if !self.executions_by_venue.contains_key(venue) {
+            let prefix = StorageKey::VenueExecutions {
+                venue_hash: env::sha256(venue.as_bytes()),
+            };
+            self.executions_by_venue
+                .insert(venue.to_string(), Vector::new(prefix));
+        }
+        if let Some(hashes) = self.executions_by_venue.get_mut(venue) {
+            hashes.push(intent_hash.to_string());
+        }
+    }
+
+    fn review_window_elapsed(&self, correction: &LogCorrection) -> bool {
+        env::block_timestamp()
+            > correction.proposed_at + self.parameter("correction_review_window_ns")
+    }
+
+    fn parameter(&self, key: &str) -> u64 {
+        self.parameters.get(key).copied().unwrap_or_else(|| {
+            PARAMETERS
+                .iter()
+                .find(|spec| spec.key == key)
+                .map(|spec| spec.default)
+                .unwrap_or_else(|| env::panic_str(&format!("Unknown parameter: {}", key)))
+        })
+    }
+
+    fn review_log_correction(&mut self, intent_hash: String, correction_id: u32, approve: bool) {
+        let reviewer_id = env::predecessor_account_id();
+        require!(
+            self.reviewers.contains(&reviewer_id),
+            "Only reviewers can review log corrections"
+        );
+        let mut corrections = self.get_log_corrections(intent_hash.clone());
+        let correction = corrections
+            .get_mut(correction_id as usize)
+            .unwrap_or_else(|| env::panic_str("Unknown correction"));
+        require!(
+            correction.status == CorrectionStatus::Pending,
+            "Correction already reviewed"
+        );
+        require!(
+            !self.review_window_elapsed(correction),
+            "Correction review window has elapsed"
+        );
+
+        correction.status = if approve {
+            CorrectionStatus::Approved
+        } else {
+            CorrectionStatus::Rejected
+        };
+        correction.reviewed_by = Some(reviewer_id.to_string());
+        correction.reviewed_at = Some(env::block_timestamp());
+        self.log_corrections
+            .insert(intent_hash.clone(), corrections);
+        let event = if approve {
+            TimelineEvent::CorrectionApproved
+        } else {
+            TimelineEvent::CorrectionRejected
+        };
+        self.record_timeline(
+            &intent_hash,
+            event,
+            Some(format!("correction {}", correction_id)),
+        );
+
+        let market = self.event_market(&intent_hash);
+        let topic = EventEmitter::emit_log_correction_reviewed(
+            intent_hash,
+            correction_id,
+            reviewer_id,
+            approve,
+            &market,
+        );
+        self.record_topic(topic);
+    }
+}
+
+/// Parse a non-negative decimal string into fixed-point units, truncating
+/// digits beyond `decimals`
+fn parse_units(amount: &str, decimals: u32) -> Option<u128> {
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if whole.is_empty()
+        || !whole.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(decimals as usize)
+        .collect();
+    whole
+        .parse::<u128>()
+        .ok()?
+        .checked_mul(10u128.pow(decimals))?
+        .checked_add(fraction.parse::<u128>().unwrap_or(0))
+}
+
+/// Insert into an already sorted Vec, keeping it sorted; false if present
+fn insert_sorted<T: Ord>(items: &mut Vec<T>, item: T) -> bool {
+    match items.binary_search(&item) {
+        Ok(_) => false,
+        Err(position) => {
+            items.insert(position, item);
+            true
+        }
+    }
+}
+
+/// RFC 4648 base64; url_safe selects the unpadded URL alphabet
+fn base64_encode(bytes: &[u8], url_safe: bool) -> String {
+    let alphabet: &[u8; 64] = if url_safe {
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+    } else {
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+    };
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            encoded.push(alphabet[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if !url_safe {
+            for _ in chunk.len()..3 {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// "YYYY-MM-DDTHH:MM:SS[.fff]Z" to nanoseconds since the Unix epoch; None
+/// for malformed or pre-1970 timestamps
+fn parse_deadline_ns(deadline: &str) -> Option<u64> {
+    let s = deadline.trim().strip_suffix('Z')?;
+    let s = s.split_once('.').map_or(s, |(whole, _)| whole);
+    let b = s.as_bytes();
+    if b.len() != 19
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || b[10] != b'T'
+        || b[13] != b':'
+        || b[16] != b':'
+    {
+        return None;
+    }
+    let field = |from: usize, to: usize| s[from..to].parse::<u64>().ok();
+    let (year, month, day) = (field(0, 4)?, field(5, 7)?, field(8, 10)?);
+    let (hour, minute, second) = (field(11, 13)?, field(14, 16)?, field(17, 19)?);
+    if year < 1970
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    // Days since epoch for the proleptic Gregorian calendar (Hinnant's days_from_civil)
+    let y = if month <= 2 { year - 1 } else { year };
+    let (era, yoe) = (y / 400, y % 400);
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    (days * 86_400 + hour * 3_600 + minute * 60 + second).checked_mul(1_000_000_000)
+}
+
+/// 8-4-4-4-12 hex groups with version nibble 4 and RFC 4122 variant
+fn is_uuid_v4(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            14 => *b == b'4',
+            19 => matches!(b.to_ascii_lowercase(), b'8' | b'9' | b'a' | b'b'),
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+fn format_units(units: u128, decimals: u32) -> String {
+    let scale = 10u128.pow(decimals);
+    let (whole, fraction) = (units / scale, units % scale);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
 // Tests updated for V2 schema
 #[cfg(test)]
 mod tests;
 
-// #[cfg(test)]
-// mod canonicalization;
-
 // #[cfg(test)]
 // mod canonicalization_proptest;
-
-// #[cfg(test)]
-// mod events;
@@ -13,6 +13,52 @@ fn setup_test_context() {
     testing_env!(context.build());
 }
 
+/// Test helper to switch the caller and block time
+fn set_context(predecessor: AccountId, block_timestamp: u64) {
+    let mut context = VMContextBuilder::new();
+    context
+        .current_account_id(accounts(0))
+        .predecessor_account_id(predecessor)
+        .block_timestamp(block_timestamp);
+    testing_env!(context.build());
+}
+
+/// Helper to create an execution log for a solver
+fn create_test_execution_log(intent_hash: &str, solver: &AccountId) -> ExecutionLog {
+    ExecutionLog {
+        intent_hash: intent_hash.to_string(),
+        solver_id: solver.to_string(),
+        venue: "binance".to_string(),
+        fill_price: "50000.0".to_string(),
+        notional: "50000.0".to_string(),
+        fees_bps: 30,
+        status: "completed".to_string(),
+        timestamp: 1_000_000_000,
+        fill_source: FillSource::Venue,
+        quote_id: None,
+    }
+}
+
+/// Helper to create stored intent metadata
+fn create_test_metadata(intent_hash: &str, symbol: &str, size: &str, timestamp: u64) -> IntentMetadata {
+    IntentMetadata {
+        intent_hash: intent_hash.to_string(),
+        solver_id: accounts(2).to_string(),
+        instrument: "perp".to_string(),
+        symbol: symbol.to_string(),
+        side: "long".to_string(),
+        size: size.to_string(),
+        timestamp,
+        tags: Vec::new(),
+        trace_id: None,
+        cancelled_at: None,
+        amends: None,
+        amended_by: None,
+        trigger: None,
+        triggered_at: None,
+    }
+}
+
 /// Helper to create a valid V2 derivatives intent
 fn create_test_intent_v2() -> DerivativesIntentV2 {
     DerivativesIntentV2 {
@@ -182,9 +228,44 @@ fn test_add_authorized_solver() {
     assert_eq!(contract.authorized_solvers[0], accounts(1));
     
     // Add another solver
-    contract.add_authorized_solver(solver.clone());
+    contract.add_authorized_solver(solver.clone(), "solver onboarding".to_string());
     assert_eq!(contract.authorized_solvers.len(), 2);
     assert_eq!(contract.authorized_solvers[1], solver);
+    let actions = contract.get_admin_actions(None, None);
+    assert_eq!(actions[0].action, "add_authorized_solver");
+    assert_eq!(actions[0].target, solver.to_string());
+}
+
+#[test]
+#[should_panic(expected = "Only treasury can add authorized solvers")]
+fn test_add_authorized_solver_requires_treasury() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    set_context(accounts(2), 1_000_000_000);
+    contract.add_authorized_solver(accounts(2), "self service".to_string());
+}
+
+#[test]
+fn test_migrate_from_v1_layout() {
+    setup_test_context();
+    let old = ContractV1 {
+        version: "1.0.0".to_string(),
+        authorized_solvers: vec![accounts(3), accounts(2)],
+        intent_metadata_keys: vec!["h1".to_string()],
+        execution_log_keys: vec!["h1".to_string()],
+    };
+    let mut contract = Contract::from_v1(old, accounts(1));
+    assert_eq!(contract.get_contract_version(), "1.0.0");
+    assert_eq!(contract.get_authorized_solvers(), vec![accounts(1), accounts(2), accounts(3)]);
+    assert_eq!(contract.intent_metadata_keys, vec!["h1"]);
+
+    // A migrated key is still indexed the first time its metadata is stored
+    contract.add_intent_tag("hedge".to_string(), "taxonomy".to_string());
+    let mut metadata = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    metadata.tags = vec!["hedge".to_string()];
+    contract.insert_intent_metadata("h1".to_string(), metadata);
+    assert_eq!(contract.intent_metadata_keys, vec!["h1"]);
+    assert_eq!(contract.get_intent_tags()[0].intent_count, 1);
 }
 
 #[test]
@@ -200,8 +281,8 @@ fn test_get_authorized_solvers() {
     assert_eq!(solvers[0], accounts(1));
     
     // Add more solvers
-    contract.add_authorized_solver(solver1.clone());
-    contract.add_authorized_solver(solver2.clone());
+    contract.add_authorized_solver(solver1.clone(), "solver onboarding".to_string());
+    contract.add_authorized_solver(solver2.clone(), "solver onboarding".to_string());
     
     let solvers = contract.get_authorized_solvers();
     assert_eq!(solvers.len(), 3);
@@ -338,6 +419,13 @@ fn test_intent_metadata() {
         side: "long".to_string(),
         size: "1000.0".to_string(),
         timestamp: 1000000000,
+        tags: Vec::new(),
+        trace_id: None,
+        cancelled_at: None,
+        amends: None,
+        amended_by: None,
+        trigger: None,
+        triggered_at: None,
     };
     
     assert_eq!(metadata.intent_hash, "abc123");
@@ -360,6 +448,8 @@ fn test_execution_log() {
         fees_bps: 30,
         status: "completed".to_string(),
         timestamp: 1000000000,
+        fill_source: FillSource::Venue,
+        quote_id: None,
     };
     
     assert_eq!(log.intent_hash, "abc123");
@@ -401,4 +491,1850 @@ fn test_venue_allowlist() {
     for venue in venues {
         assert_eq!(venue.to_lowercase(), *venue);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_log_correction_approval_keeps_original() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_reviewer(accounts(3), "onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+
+    let mut corrected = create_test_execution_log("abc123", &accounts(2));
+    corrected.fill_price = "50100.0".to_string();
+    let correction_id = contract.propose_log_correction(
+        "abc123".to_string(),
+        corrected,
+        "ipfs://venue-fill-report".to_string(),
+    );
+    assert_eq!(correction_id, 0);
+
+    set_context(accounts(3), 2_000_000_000);
+    contract.approve_log_correction("abc123".to_string(), correction_id);
+
+    let original = contract.get_execution_log("abc123".to_string()).unwrap();
+    assert_eq!(original.fill_price, "50000.0");
+    let effective = contract.get_effective_execution_log("abc123".to_string()).unwrap();
+    assert_eq!(effective.fill_price, "50100.0");
+
+    let corrections = contract.get_log_corrections("abc123".to_string());
+    assert_eq!(corrections.len(), 1);
+    assert_eq!(corrections[0].status, CorrectionStatus::Approved);
+    assert_eq!(corrections[0].supersedes, None);
+}
+
+#[test]
+#[should_panic(expected = "Only the logging solver can propose a correction")]
+fn test_log_correction_requires_logging_solver() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+
+    set_context(accounts(4), 1_000_000_000);
+    contract.propose_log_correction(
+        "abc123".to_string(),
+        create_test_execution_log("abc123", &accounts(4)),
+        "ipfs://venue-fill-report".to_string(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Correction review window has elapsed")]
+fn test_log_correction_review_window() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_reviewer(accounts(3), "onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+    contract.propose_log_correction(
+        "abc123".to_string(),
+        create_test_execution_log("abc123", &accounts(2)),
+        "ipfs://venue-fill-report".to_string(),
+    );
+
+    set_context(accounts(3), 1_000_000_000 + 25 * 60 * 60 * 1_000_000_000);
+    contract.reject_log_correction("abc123".to_string(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Execution already logged; use propose_log_correction to amend it")]
+fn test_log_execution_rejects_second_log() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+    let mut relogged = create_test_execution_log("abc123", &accounts(2));
+    relogged.fill_price = "1.0".to_string();
+    contract.log_execution("abc123".to_string(), relogged, None);
+}
+
+#[test]
+#[should_panic(expected = "Only authorized solvers can log executions")]
+fn test_log_execution_requires_authorized_solver() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Execution log solver_id must be the caller")]
+fn test_log_execution_rejects_foreign_solver_id() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(4)), None);
+}
+
+#[test]
+fn test_pending_intents_sorted_and_filtered() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "2.0", 300));
+    contract.insert_intent_metadata("h2".to_string(), create_test_metadata("h2", "BTC-USD", "0.5", 100));
+    contract.insert_intent_metadata("h3".to_string(), create_test_metadata("h3", "ETH-USD", "10.0", 200));
+    contract.insert_intent_metadata("h4".to_string(), create_test_metadata("h4", "BTC-USD", "1.0", 50));
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h4".to_string(), create_test_execution_log("h4", &accounts(2)), None);
+
+    let all = contract.get_pending_intents(PendingIntentFilter::default());
+    let hashes: Vec<&str> = all.iter().map(|p| p.intent_hash.as_str()).collect();
+    assert_eq!(hashes, vec!["h2", "h3", "h1"]);
+
+    let btc_large = contract.get_pending_intents(PendingIntentFilter {
+        symbol: Some("BTC-USD".to_string()),
+        min_size: Some("1".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(btc_large.len(), 1);
+    assert_eq!(btc_large[0].intent_hash, "h1");
+}
+
+#[test]
+fn test_pending_intents_by_deadline_and_executable_only() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    for (hash, deadline) in [("late", 90_000_000_000u64), ("soon", 20_000_000_000), ("gone", 5_000_000_000)] {
+        contract.insert_intent_metadata(hash.to_string(), create_test_metadata(hash, "BTC-USD", "1.0", 100));
+        contract.index_deadline(hash, deadline);
+    }
+    contract.insert_intent_metadata("open".to_string(), create_test_metadata("open", "BTC-USD", "1.0", 50));
+    let mut stop = create_test_metadata("stop", "BTC-USD", "1.0", 100);
+    stop.trigger = Some(TriggerCondition {
+        trigger_price: "45000".to_string(),
+        direction: TriggerDirection::Below,
+        price_source: "mark".to_string(),
+    });
+    contract.insert_intent_metadata("stop".to_string(), stop);
+    contract.insert_intent_metadata("child".to_string(), create_test_metadata("child", "BTC-USD", "1.0", 100));
+    contract.bracket_parents.insert("child".to_string(), "late".to_string());
+
+    set_context(accounts(2), 10_000_000_000);
+    let pending = contract.get_pending_intents(PendingIntentFilter::default());
+    let hashes: Vec<&str> = pending.iter().map(|p| p.intent_hash.as_str()).collect();
+    assert_eq!(hashes, vec!["soon", "late", "open"]);
+}
+
+#[test]
+#[should_panic(expected = "Invalid min_size: lots")]
+fn test_pending_intents_rejects_invalid_min_size() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    contract.get_pending_intents(PendingIntentFilter {
+        min_size: Some("lots".to_string()),
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_pending_intents_page_the_pending_index() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    for hash in ["h1", "h2", "h3", "h4"] {
+        contract.insert_intent_metadata(hash.to_string(), create_test_metadata(hash, "BTC-USD", "1.0", 100));
+    }
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    assert!(!contract.pending_intents.contains("h1"));
+
+    let page = |cursor| {
+        contract
+            .get_pending_intents(PendingIntentFilter { cursor: Some(cursor), limit: Some(2), ..Default::default() })
+            .into_iter()
+            .map(|p| p.intent_hash)
+            .collect::<Vec<_>>()
+    };
+    // The executed intent keeps its position, so later cursors do not move
+    assert_eq!(page(0), vec!["h2".to_string()]);
+    assert_eq!(page(2), vec!["h3".to_string(), "h4".to_string()]);
+    assert!(page(4).is_empty());
+}
+
+#[test]
+fn test_log_execution_idempotency_key_replay() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    let first = contract.log_execution(
+        "abc123".to_string(),
+        create_test_execution_log("abc123", &accounts(2)),
+        Some("retry-1".to_string()),
+    );
+
+    // A retried transaction with a different payload must not overwrite the log
+    let mut retried = create_test_execution_log("abc123", &accounts(2));
+    retried.fill_price = "1.0".to_string();
+    let replay = contract.log_execution("abc123".to_string(), retried, Some("retry-1".to_string()));
+
+    assert_eq!(first, replay);
+    assert_eq!(contract.get_execution_log("abc123".to_string()).unwrap().fill_price, "50000.0");
+    assert_eq!(contract.execution_log_keys.len(), 1);
+}
+
+#[test]
+fn test_idempotency_key_reusable_after_ttl() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let record_key = format!("{}:retry-1", accounts(2));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), Some("retry-1".to_string()));
+    contract.log_execution("h2".to_string(), create_test_execution_log("h2", &accounts(2)), Some("retry-2".to_string()));
+
+    // Past the TTL the expired record is dropped and the key serves a new intent
+    let reused_at = 1_000_000_000 + IDEMPOTENCY_KEY_TTL_NS + 1;
+    set_context(accounts(2), reused_at);
+    contract.log_execution("h3".to_string(), create_test_execution_log("h3", &accounts(2)), Some("retry-1".to_string()));
+    assert_eq!(contract.idempotency_records.get(&record_key).unwrap().intent_hash, "h3");
+
+    // The sweep reclaims the stale retry-2 record but keeps the reused key's new one
+    contract.sweep_expired_intents(None);
+    assert!(contract.idempotency_records.get(&format!("{}:retry-2", accounts(2))).is_none());
+    assert_eq!(contract.idempotency_records.get(&record_key).unwrap().recorded_at, reused_at);
+    assert_eq!(contract.idempotency_queue_head, 2);
+
+    set_context(accounts(2), reused_at + IDEMPOTENCY_KEY_TTL_NS + 1);
+    contract.sweep_expired_intents(None);
+    assert!(contract.idempotency_records.get(&record_key).is_none());
+    assert_eq!(contract.idempotency_queue_head, 3);
+}
+
+#[test]
+fn test_intent_draft_cosigning_flow() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_trader(accounts(2), "onboarding".to_string());
+    contract.add_approver(accounts(3), "onboarding".to_string());
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+
+    set_context(accounts(2), 1_000_000_000);
+    let draft_id = contract.propose_intent(intent_json);
+
+    set_context(accounts(3), 2_000_000_000);
+    contract.approve_intent(draft_id);
+
+    set_context(accounts(1), 3_000_000_000);
+    contract.store_draft_metadata(draft_id);
+
+    let draft = contract.get_intent_draft(draft_id).unwrap();
+    assert_eq!(draft.status, DraftStatus::Stored);
+    assert_eq!(draft.approved_by, Some(accounts(3).to_string()));
+    let metadata = contract.get_intent_metadata(draft.intent_hash).unwrap();
+    assert_eq!(metadata.symbol, "BTC-USD");
+    assert_eq!(metadata.solver_id, accounts(1).to_string());
+}
+
+#[test]
+#[should_panic(expected = "Proposer cannot approve their own draft")]
+fn test_intent_draft_requires_second_signer() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_trader(accounts(2), "onboarding".to_string());
+    contract.add_approver(accounts(2), "onboarding".to_string());
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+
+    set_context(accounts(2), 1_000_000_000);
+    let draft_id = contract.propose_intent(intent_json);
+    contract.approve_intent(draft_id);
+}
+
+#[test]
+fn test_settlement_preference() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    assert!(contract.get_settlement_preference(accounts(2)).is_none());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.set_settlement_preference("USDT".to_string(), " Arbitrum ".to_string());
+
+    let preference = contract.get_settlement_preference(accounts(2)).unwrap();
+    assert_eq!(preference.token, "USDT");
+    assert_eq!(preference.chain, "arbitrum");
+
+    contract.clear_settlement_preference();
+    assert!(contract.get_settlement_preference(accounts(2)).is_none());
+}
+
+#[test]
+fn test_vector_registry_and_check() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let input = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let expected_hash = contract.run_vector_check(input.clone(), String::new()).computed_hash.unwrap();
+
+    contract.add_test_vector(
+        "1.0.0".to_string(),
+        TestVector {
+            name: "btc perp long".to_string(),
+            input: input.clone(),
+            expected_hash: expected_hash.clone(),
+        },
+        "golden vector".to_string(),
+    );
+    assert_eq!(contract.get_test_vectors("1.0.0".to_string()).len(), 1);
+    assert!(contract.get_test_vectors("2.0.0".to_string()).is_empty());
+
+    assert!(contract.run_vector_check(input, expected_hash).matches);
+    let malformed = contract.run_vector_check("{".to_string(), String::new());
+    assert!(!malformed.matches);
+    assert!(malformed.error.unwrap().contains("Invalid intent JSON"));
+}
+
+#[test]
+fn test_symbol_stats_and_leaderboard() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    contract.insert_intent_metadata("h2".to_string(), create_test_metadata("h2", "BTC-USD", "1.0", 100));
+    contract.insert_intent_metadata("h3".to_string(), create_test_metadata("h3", "ETH-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    let mut log = create_test_execution_log("h1", &accounts(2));
+    log.notional = "1000.5".to_string();
+    contract.log_execution("h1".to_string(), log, None);
+
+    // Eight days later only the new trade is inside the 24h and 7d windows
+    set_context(accounts(2), 1_000_000_000 + 8 * 24 * 60 * 60 * 1_000_000_000);
+    contract.log_execution("h2".to_string(), create_test_execution_log("h2", &accounts(2)), None);
+    contract.log_execution("h3".to_string(), create_test_execution_log("h3", &accounts(2)), None);
+
+    let btc = contract.get_symbol_stats("BTC-USD".to_string()).unwrap();
+    assert_eq!(btc.trade_count, 2);
+    assert_eq!(btc.total_notional, "51000.5");
+    assert_eq!(btc.volume_24h, "50000");
+    assert_eq!(btc.volume_7d, "50000");
+    assert_eq!(btc.volume_30d, "51000.5");
+
+    let leaderboard = contract.get_symbol_leaderboard(10);
+    assert_eq!(leaderboard.len(), 2);
+    assert_eq!(leaderboard[0].symbol, "BTC-USD");
+    assert!(contract.get_symbol_stats("SOL-USD".to_string()).is_none());
+}
+
+#[test]
+fn test_nonce_policies() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let signer = accounts(1).to_string();
+    assert_eq!(contract.get_nonce_policy(signer.clone()), NoncePolicy::Unrestricted);
+
+    contract.set_nonce_policy(NoncePolicy::Uuid);
+    assert!(contract
+        .check_nonce(signer.clone(), "3f2b8c1e-9d4a-4b6e-8f7a-0c1d2e3f4a5b".to_string())
+        .is_ok());
+    assert!(contract.check_nonce(signer.clone(), "12345".to_string()).is_err());
+
+    contract.set_nonce_policy(NoncePolicy::Counter);
+    contract.last_nonces.insert(signer.clone(), "41".to_string());
+    assert!(contract.check_nonce(signer.clone(), "42".to_string()).is_ok());
+    let stale = contract.check_nonce(signer.clone(), "40".to_string());
+    assert!(stale.unwrap_err().contains("is not greater than 41"));
+    assert!(contract.check_nonce(signer, "41".to_string()).unwrap_err().contains("already used"));
+}
+
+#[test]
+fn test_executions_by_venue() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let solver = accounts(2);
+    contract.add_authorized_solver(solver.clone(), "solver onboarding".to_string());
+
+    set_context(solver.clone(), 0);
+    for (i, venue) in ["binance", "gmx", "binance", "binance"].iter().enumerate() {
+        let hash = format!("hash_{}", i);
+        let mut log = create_test_execution_log(&hash, &solver);
+        log.venue = venue.to_string();
+        log.timestamp = (i as u64 + 1) * 100;
+        contract.log_execution(hash, log, None);
+    }
+
+    let page = contract.get_executions_by_venue("binance".to_string(), Some(150), None, None, Some(1));
+    assert_eq!(page.executions.len(), 1);
+    assert_eq!(page.executions[0].intent_hash, "hash_2");
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = contract.get_executions_by_venue("binance".to_string(), Some(150), None, page.next_cursor, None);
+    assert_eq!(page.executions.len(), 1);
+    assert_eq!(page.executions[0].intent_hash, "hash_3");
+    assert_eq!(page.next_cursor, None);
+
+    assert_eq!(contract.get_executions_by_venue("gmx".to_string(), None, None, None, None).executions.len(), 1);
+    assert_eq!(contract.get_executions_by_venue("binance".to_string(), None, None, None, None).executions.len(), 3);
+}
+
+#[test]
+fn test_policy_rules_reject_matching_intents() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let rule_id = contract.add_policy_rule(
+        "No high leverage on BTC".to_string(),
+        Some("BTC-USD".to_string()),
+        vec![RuleCondition {
+            field: "leverage".to_string(),
+            operator: RuleOperator::Gt,
+            value: "5".to_string(),
+        }],
+        "risk limit".to_string(),
+    );
+
+    let intent = create_test_intent_v2();
+    let err = contract.validate_v2_intent(intent.clone()).unwrap_err();
+    assert!(err.contains(&format!("Rejected by policy rule {}", rule_id)));
+    assert!(err.contains("leverage Gt 5"));
+
+    // Out of scope symbols and non-matching values pass
+    let mut other_symbol = intent.clone();
+    other_symbol.derivatives.symbol = "ETH-USD".to_string();
+    assert!(contract.validate_v2_intent(other_symbol).is_ok());
+    let mut low_leverage = intent;
+    low_leverage.derivatives.leverage = "5".to_string();
+    assert!(contract.validate_v2_intent(low_leverage.clone()).is_ok());
+
+    contract.remove_policy_rule(rule_id, "superseded".to_string());
+    assert!(contract.get_policy_rules().is_empty());
+}
+
+#[test]
+fn test_store_intent_metadata_counts_policy_rule_hits() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let rule_id = contract.add_policy_rule(
+        "No high leverage on BTC".to_string(),
+        Some("BTC-USD".to_string()),
+        vec![RuleCondition {
+            field: "leverage".to_string(),
+            operator: RuleOperator::Gt,
+            value: "5".to_string(),
+        }],
+        "risk limit".to_string(),
+    );
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let intent_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let metadata = create_test_metadata(&intent_hash, "BTC-USD", "1000.0", 100);
+
+    let result = contract.store_intent_metadata(intent_hash.clone(), metadata, intent_json);
+    assert!(result.starts_with(&format!("Rejected by policy rule {}", rule_id)));
+    assert!(contract.get_intent_metadata(intent_hash).is_none());
+    assert_eq!(contract.get_policy_rule_hits(rule_id), 1);
+    assert_eq!(contract.get_last_nonce(accounts(1).to_string()), None);
+}
+
+#[test]
+#[should_panic(expected = "requires a numeric field")]
+fn test_policy_rule_rejects_ordering_on_text_field() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_policy_rule(
+        "bad".to_string(),
+        None,
+        vec![RuleCondition {
+            field: "side".to_string(),
+            operator: RuleOperator::Gt,
+            value: "long".to_string(),
+        }],
+        "risk limit".to_string(),
+    );
+}
+
+#[test]
+fn test_preview_netting() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut short_two = create_test_metadata("s2", "BTC-USD", "2.0", 200);
+    short_two.side = "short".to_string();
+    let mut short_half = create_test_metadata("s05", "BTC-USD", "0.5", 300);
+    short_half.side = "short".to_string();
+    contract.insert_intent_metadata("l2".to_string(), create_test_metadata("l2", "BTC-USD", "2.0", 100));
+    contract.insert_intent_metadata("s2".to_string(), short_two);
+    contract.insert_intent_metadata("s05".to_string(), short_half);
+    contract.insert_intent_metadata("e1".to_string(), create_test_metadata("e1", "ETH-USD", "1.25", 400));
+
+    let preview = contract.preview_netting(vec![
+        "s05".to_string(),
+        "l2".to_string(),
+        "e1".to_string(),
+        "s2".to_string(),
+        "l2".to_string(),
+        "missing".to_string(),
+    ]);
+    assert_eq!(preview.offsetting_intents, vec![("l2".to_string(), "s2".to_string())]);
+    assert_eq!(preview.unknown_intents, vec!["missing".to_string()]);
+    let btc = preview.residuals.iter().find(|r| r.symbol == "BTC-USD").unwrap();
+    assert_eq!((btc.side.as_str(), btc.size.as_str()), ("short", "0.5"));
+    let eth = preview.residuals.iter().find(|r| r.symbol == "ETH-USD").unwrap();
+    assert_eq!((eth.side.as_str(), eth.size.as_str()), ("long", "1.25"));
+}
+
+#[test]
+fn test_preview_netting_treats_buy_sell_as_long_short() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut buy = create_test_metadata("b1", "BTC-USD", "1.0", 100);
+    buy.side = "buy".to_string();
+    let mut sell = create_test_metadata("s1", "BTC-USD", "1.0", 200);
+    sell.side = "SELL".to_string();
+    contract.insert_intent_metadata("b1".to_string(), buy);
+    contract.insert_intent_metadata("s1".to_string(), sell);
+
+    let preview = contract.preview_netting(vec!["b1".to_string(), "s1".to_string()]);
+    assert_eq!(preview.offsetting_intents, vec![("b1".to_string(), "s1".to_string())]);
+    assert_eq!(preview.residuals[0].side, "flat");
+}
+
+#[test]
+fn test_preview_netting_keeps_size_precision() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.insert_intent_metadata("l1".to_string(), create_test_metadata("l1", "BTC-USD", "0.00000001", 100));
+    let mut short = create_test_metadata("s1", "BTC-USD", "0.00000002", 200);
+    short.side = "short".to_string();
+    contract.insert_intent_metadata("s1".to_string(), short);
+
+    let preview = contract.preview_netting(vec!["l1".to_string(), "s1".to_string()]);
+    assert!(preview.offsetting_intents.is_empty());
+    assert_eq!((preview.residuals[0].side.as_str(), preview.residuals[0].size.as_str()), ("short", "0.00000001"));
+}
+
+#[test]
+fn test_store_intent_metadata_checks_canonical_intent() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let intent_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let metadata = create_test_metadata(&intent_hash, "BTC-USD", "1000.0", 100);
+
+    contract.store_intent_metadata(intent_hash.clone(), metadata, intent_json);
+    assert_eq!(contract.get_intent_metadata(intent_hash).unwrap().size, "1000");
+    assert_eq!(contract.get_topics(), vec!["BTC-USD:perp:intent_submitted".to_string()]);
+}
+
+#[test]
+fn test_store_intent_metadata_stores_canonical_fields() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut intent = create_test_intent_v2();
+    intent.derivatives.side = "LONG".to_string();
+    let intent_json = serde_json::to_string(&intent).unwrap();
+    let intent_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let mut metadata = create_test_metadata(&intent_hash, "btc-usd", "1000.00", 100);
+    metadata.side = "LONG".to_string();
+
+    contract.store_intent_metadata(intent_hash.clone(), metadata, intent_json);
+    let stored = contract.get_intent_metadata(intent_hash.clone()).unwrap();
+    assert_eq!((stored.symbol.as_str(), stored.side.as_str(), stored.size.as_str()), ("BTC-USD", "long", "1000"));
+    assert_eq!(contract.preview_netting(vec![intent_hash]).residuals[0].side, "long");
+}
+
+#[test]
+#[should_panic(expected = "Metadata symbol ETH-USD does not match intent BTC-USD")]
+fn test_store_intent_metadata_rejects_mismatch() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let intent_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let metadata = create_test_metadata(&intent_hash, "ETH-USD", "1000.0", 100);
+
+    contract.store_intent_metadata(intent_hash, metadata, intent_json);
+}
+
+#[test]
+#[should_panic(expected = "Nonce 12345 already used by bob")]
+fn test_store_intent_metadata_consumes_nonce() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut intent = create_test_intent_v2();
+    let intent_json = serde_json::to_string(&intent).unwrap();
+    let intent_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    contract.store_intent_metadata(
+        intent_hash.clone(),
+        create_test_metadata(&intent_hash, "BTC-USD", "1000.0", 100),
+        intent_json,
+    );
+    assert_eq!(contract.get_last_nonce(accounts(1).to_string()), Some("12345".to_string()));
+
+    intent.derivatives.size = "500.0".to_string();
+    let replay_json = serde_json::to_string(&intent).unwrap();
+    let replay_hash = Contract::canonical_intent(&replay_json).unwrap().hash(HASH_VERSION_LEGACY);
+    contract.store_intent_metadata(
+        replay_hash.clone(),
+        create_test_metadata(&replay_hash, "BTC-USD", "500.0", 100),
+        replay_json,
+    );
+}
+
+#[test]
+fn test_event_schemas_cover_all_events() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let events: Vec<String> = contract.get_event_schemas().into_iter().map(|s| s.event).collect();
+    for event in ["intent_submitted", "execution_logged", "log_correction_approved", "log_correction_rejected", "intent_cancelled", "intent_expired", "intent_amended", "document_anchored", "bracket_resolved", "admin_action"] {
+        assert!(events.contains(&event.to_string()), "missing schema for {}", event);
+    }
+    // Schemas describe the data objects as emitted, topic and trace_id included
+    for schema in contract.get_event_schemas() {
+        let properties = &schema.schema["properties"];
+        assert!(properties.get("topic").is_some() && properties.get("trace_id").is_some(), "{}", schema.event);
+        assert!(schema.schema["required"].as_array().unwrap().contains(&serde_json::json!("topic")));
+    }
+}
+
+#[test]
+fn test_intent_timeline_records_lifecycle() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_reviewer(accounts(3), "onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.insert_intent_metadata("abc123".to_string(), create_test_metadata("abc123", "BTC-USD", "1.0", 100));
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+    contract.propose_log_correction(
+        "abc123".to_string(),
+        create_test_execution_log("abc123", &accounts(2)),
+        "ipfs://venue-fill-report".to_string(),
+    );
+    set_context(accounts(3), 2_000_000_000);
+    contract.reject_log_correction("abc123".to_string(), 0);
+
+    let timeline = contract.get_intent_timeline("abc123".to_string());
+    let events: Vec<TimelineEvent> = timeline.iter().map(|e| e.event.clone()).collect();
+    assert_eq!(
+        events,
+        vec![
+            TimelineEvent::Stored,
+            TimelineEvent::Executed,
+            TimelineEvent::CorrectionProposed,
+            TimelineEvent::CorrectionRejected,
+        ]
+    );
+    assert_eq!(timeline[3].actor, accounts(3).to_string());
+    assert_eq!(timeline[3].timestamp, 2_000_000_000);
+    assert!(contract.get_intent_timeline("unknown".to_string()).is_empty());
+}
+
+#[test]
+fn test_dead_letter_queue_retry_policy() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    assert_eq!(contract.report_failed_execution("h1".to_string(), "venue_rejected".to_string()), 1);
+    contract.report_failed_execution("h2".to_string(), "timeout".to_string());
+    contract.retry_failed_execution("h1".to_string());
+    assert!(contract.get_dead_letter_queue(None, None)[0].retry_requested);
+    assert_eq!(contract.report_failed_execution("h1".to_string(), "venue_rejected".to_string()), 2);
+
+    let page = contract.get_dead_letter_queue(Some(1), Some(1));
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].intent_hash, "h2");
+
+    // A successful execution clears the entry
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    let queue = contract.get_dead_letter_queue(None, None);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].intent_hash, "h2");
+}
+
+#[test]
+#[should_panic(expected = "Maximum execution attempts reached")]
+fn test_dead_letter_retry_limit() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    for _ in 0..3 {
+        contract.report_failed_execution("h1".to_string(), "venue_rejected".to_string());
+    }
+    contract.retry_failed_execution("h1".to_string());
+}
+
+#[test]
+fn test_export_account_activity_fix_records() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let trader = accounts(3).to_string();
+    let mut short = create_test_metadata("h2", "ETH-USD", "3.0", 200);
+    short.side = "short".to_string();
+    contract.index_account_intent(&trader, "h1");
+    contract.index_account_intent(&trader, "h2");
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.5", 100));
+    contract.insert_intent_metadata("h2".to_string(), short);
+    // Already stored, so not indexed a second time
+    contract.index_account_intent(&trader, "h1");
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+
+    let records = contract.export_account_activity(trader.clone(), None, None, None);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].cl_ord_id, "h1");
+    assert_eq!(records[0].exec_type, "F");
+    assert_eq!(records[0].last_px.as_deref(), Some("50000.0"));
+    assert_eq!(records[0].cum_qty, "1.5");
+    assert_eq!(records[1].side, "2");
+    assert_eq!(records[1].exec_type, "0");
+    assert_eq!(records[1].cum_qty, "0");
+
+    assert_eq!(contract.export_account_activity(trader, None, Some(1), Some(1))[0].cl_ord_id, "h2");
+    assert!(contract.export_account_activity(accounts(4).to_string(), None, None, None).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Unknown side hold for h2")]
+fn test_export_account_activity_maps_fix_sides() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let trader = accounts(3).to_string();
+    let mut sell = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    sell.side = "sell".to_string();
+    let mut unknown = create_test_metadata("h2", "BTC-USD", "1.0", 200);
+    unknown.side = "hold".to_string();
+    for (hash, metadata) in [("h1", sell), ("h2", unknown)] {
+        contract.index_account_intent(&trader, hash);
+        contract.insert_intent_metadata(hash.to_string(), metadata);
+    }
+
+    assert_eq!(contract.export_account_activity(trader.clone(), None, None, Some(1))[0].side, "2");
+    contract.export_account_activity(trader, None, None, None);
+}
+
+#[test]
+#[should_panic(expected = "At most 50 entries per batch")]
+fn test_verify_intent_hashes_batch_limit() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let entries = (0..51)
+        .map(|i| IntentHashClaim {
+            intent_json: "{}".to_string(),
+            claimed_hash: format!("{:064}", i),
+        })
+        .collect();
+    contract.verify_intent_hashes_batch(entries);
+}
+
+#[test]
+fn test_verify_intent_hashes_batch_reports_per_entry() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let results = contract.verify_intent_hashes_batch(vec![
+        IntentHashClaim { intent_json: intent_json.clone(), claimed_hash: hash.clone() },
+        IntentHashClaim { intent_json, claimed_hash: "0".repeat(64) },
+        IntentHashClaim { intent_json: "not json".to_string(), claimed_hash: hash.clone() },
+    ]);
+    assert!(results[0].matches);
+    assert!(!results[1].matches);
+    assert_eq!(results[1].computed_hash.as_deref(), Some(hash.as_str()));
+    assert!(results[2].error.is_some());
+}
+
+#[test]
+fn test_solver_fill_source_distribution() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let solver = accounts(2);
+
+    set_context(solver.clone(), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &solver), None);
+    let mut internalized = create_test_execution_log("h2", &solver);
+    internalized.fill_source = FillSource::Internalized;
+    contract.log_execution("h2".to_string(), internalized, None);
+
+    let counts = contract.get_solver_fill_sources(solver.to_string());
+    assert_eq!((counts.venue, counts.internalized, counts.rfq_cross), (1, 1, 0));
+}
+
+#[test]
+fn test_event_topics_recorded() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_reviewer(accounts(3), "onboarding".to_string());
+    contract.insert_intent_metadata("abc123".to_string(), create_test_metadata("abc123", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("abc123".to_string(), create_test_execution_log("abc123", &accounts(2)), None);
+    contract.propose_log_correction(
+        "abc123".to_string(),
+        create_test_execution_log("abc123", &accounts(2)),
+        "ipfs://venue-fill-report".to_string(),
+    );
+    set_context(accounts(3), 2_000_000_000);
+    contract.approve_log_correction("abc123".to_string(), 0);
+
+    assert_eq!(
+        contract.get_topics(),
+        vec![
+            "BTC-USD:perp:execution_logged".to_string(),
+            "BTC-USD:perp:log_correction_approved".to_string(),
+            "BTC-USD:perp:log_correction_proposed".to_string(),
+            "unknown:unknown:admin_action".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_base64_encoding_variants() {
+    assert_eq!(base64_encode(b"", false), "");
+    assert_eq!(base64_encode(b"f", false), "Zg==");
+    assert_eq!(base64_encode(b"fo", false), "Zm8=");
+    assert_eq!(base64_encode(b"foobar", false), "Zm9vYmFy");
+    assert_eq!(base64_encode(&[0xfb, 0xff], false), "+/8=");
+    assert_eq!(base64_encode(&[0xfb, 0xff], true), "-_8");
+}
+
+#[test]
+fn test_signing_payload_requires_stored_intent() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    assert!(contract
+        .get_signing_payload("ab".repeat(32), SigningPayloadFormat::DeepLink)
+        .is_none());
+}
+
+#[test]
+fn test_collection_views_sorted_by_key() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_reviewer(accounts(4), "onboarding".to_string());
+    contract.add_reviewer(accounts(2), "onboarding".to_string());
+    contract.add_reviewer(accounts(3), "onboarding".to_string());
+    contract.add_reviewer(accounts(2), "onboarding".to_string());
+    assert_eq!(contract.get_reviewers(), vec![accounts(2), accounts(3), accounts(4)]);
+
+    contract.add_authorized_solver(accounts(0), "solver onboarding".to_string());
+    assert_eq!(contract.get_authorized_solvers(), vec![accounts(0), accounts(1)]);
+}
+
+#[test]
+fn test_domain_separated_hash_version() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let legacy = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let canonical = Contract::canonical_intent(&intent_json).unwrap();
+    let domain_hash = canonical.hash(HASH_VERSION_DOMAIN_SEPARATED);
+    assert_ne!(legacy, domain_hash);
+    assert_eq!(
+        domain_hash,
+        Canonicalizer::compute_hash(&format!("deltanear:intent:1.0.0\n{}", canonical.json))
+    );
+
+    let result = contract.verify_intent_hash(intent_json.clone(), domain_hash.clone());
+    assert!(result.matches);
+    assert_eq!(result.hash_version, Some(2));
+
+    let metadata = create_test_metadata(&domain_hash, "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata(domain_hash.clone(), metadata, intent_json);
+    assert_eq!(contract.get_intent_hash_version(domain_hash), Some(2));
+    assert_eq!(contract.get_intent_hash_version(legacy), None);
+}
+
+#[test]
+#[should_panic(expected = "Intent already stored under hash")]
+fn test_store_intent_metadata_rejects_second_hash_version() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let canonical = Contract::canonical_intent(&intent_json).unwrap();
+    let legacy = canonical.hash(HASH_VERSION_LEGACY);
+    let domain_hash = canonical.hash(HASH_VERSION_DOMAIN_SEPARATED);
+
+    let metadata = create_test_metadata(&legacy, "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata(legacy, metadata, intent_json.clone());
+    let metadata = create_test_metadata(&domain_hash, "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata(domain_hash, metadata, intent_json);
+}
+
+#[test]
+#[should_panic(expected = "Only callable from another contract")]
+fn test_submit_intent_from_contract_rejects_direct_calls() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_trader(accounts(2), "onboarding".to_string());
+
+    let mut context = VMContextBuilder::new();
+    context
+        .current_account_id(accounts(0))
+        .signer_account_id(accounts(2))
+        .predecessor_account_id(accounts(2));
+    testing_env!(context.build());
+    contract.submit_intent_from_contract("{}".to_string());
+}
+
+#[test]
+fn test_submit_intent_from_contract_tags_origin() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_trader(accounts(2), "onboarding".to_string());
+    let mut intent = create_test_intent_v2();
+    intent.signer_id = accounts(2).to_string();
+    let intent_json = serde_json::to_string(&intent).unwrap();
+
+    let mut context = VMContextBuilder::new();
+    context
+        .current_account_id(accounts(0))
+        .signer_account_id(accounts(4))
+        .predecessor_account_id(accounts(2));
+    testing_env!(context.build());
+    let draft_id = contract.submit_intent_from_contract(intent_json);
+
+    let draft = contract.get_intent_draft(draft_id).unwrap();
+    assert_eq!(draft.origin, IntentOrigin::Contract);
+    assert_eq!(draft.proposed_by, accounts(2).to_string());
+}
+
+#[test]
+fn test_parameter_registry_override_takes_effect() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let attempts = contract
+        .get_parameters()
+        .into_iter()
+        .find(|p| p.key == "max_execution_attempts")
+        .unwrap();
+    assert_eq!((attempts.value, attempts.min, attempts.max), (3, 1, 10));
+
+    contract.set_parameter("max_execution_attempts".to_string(), 5, "tuning".to_string());
+    set_context(accounts(2), 1_000_000_000);
+    for _ in 0..3 {
+        contract.report_failed_execution("h1".to_string(), "venue_rejected".to_string());
+    }
+    contract.retry_failed_execution("h1".to_string());
+    assert!(contract.get_dead_letter_queue(None, None)[0].retry_requested);
+}
+
+#[test]
+#[should_panic(expected = "Parameter draft_expiry_ns must be within")]
+fn test_parameter_registry_rejects_out_of_bounds() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.set_parameter("draft_expiry_ns".to_string(), 0, "tuning".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Unknown parameter: max_page_size")]
+fn test_parameter_registry_rejects_unknown_key() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.set_parameter("max_page_size".to_string(), 10, "tuning".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Private intent must be revealed before execution")]
+fn test_private_intent_hides_fields_until_reveal() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let signer = accounts(3).to_string();
+    set_context(accounts(3), 1_000_000_000);
+    contract.store_private_intent("h1".to_string(), signer.clone(), "2024-12-31T23:59:59Z".to_string());
+
+    let commitment = contract.get_private_intent("h1".to_string()).unwrap();
+    assert_eq!(commitment.signer_id, signer);
+    assert!(contract.get_intent_metadata("h1".to_string()).is_none());
+    assert_eq!(contract.get_intent_timeline("h1".to_string())[0].event, TimelineEvent::Committed);
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Revealed intent does not match commitment")]
+fn test_reveal_private_intent_checks_commitment() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent = create_test_intent_v2();
+    let intent_json = serde_json::to_string(&intent).unwrap();
+    let hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    contract.store_private_intent(hash.clone(), intent.signer_id.clone(), "2026-06-30T00:00:00Z".to_string());
+
+    let metadata = create_test_metadata(&hash, "BTC-USD", "1000.0", 100);
+    contract.reveal_intent_metadata(hash, metadata, intent_json);
+}
+
+#[test]
+fn test_reveal_private_intent_compares_canonical_fields() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut intent = serde_json::to_value(create_test_intent_v2()).unwrap();
+    intent["signer_id"] = serde_json::json!("BOB");
+    intent["deadline"] = serde_json::json!("2025-12-31T23:59:59.000Z");
+    let intent_json = intent.to_string();
+    let hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    contract.store_private_intent(hash.clone(), "Bob".to_string(), "2025-12-31T23:59:59Z".to_string());
+    assert_eq!(contract.get_private_intent(hash.clone()).unwrap().signer_id, "bob");
+
+    let metadata = create_test_metadata(&hash, "BTC-USD", "1000.0", 100);
+    contract.reveal_intent_metadata(hash.clone(), metadata, intent_json);
+    assert!(contract.get_private_intent(hash.clone()).is_none());
+    assert!(contract.get_intent_metadata(hash).is_some());
+}
+
+#[test]
+fn test_reveal_private_intent_without_leverage() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut intent = serde_json::to_value(create_test_intent_v2()).unwrap();
+    intent["derivatives"].as_object_mut().unwrap().remove("leverage");
+    intent["nonce"] = serde_json::json!(12345);
+    let intent_json = intent.to_string();
+    let hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    contract.store_private_intent(hash.clone(), accounts(1).to_string(), "2025-12-31T23:59:59Z".to_string());
+
+    let metadata = create_test_metadata(&hash, "BTC-USD", "1000.0", 100);
+    contract.reveal_intent_metadata(hash.clone(), metadata, intent_json);
+    assert!(contract.get_intent_metadata(hash).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Only the signer can commit a private intent")]
+fn test_private_intent_requires_signer() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.store_private_intent("h1".to_string(), accounts(3).to_string(), "2024-12-31T23:59:59Z".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Intent has a private commitment")]
+fn test_store_intent_metadata_rejects_committed_hash() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.store_private_intent("h1".to_string(), accounts(1).to_string(), "2024-12-31T23:59:59Z".to_string());
+    let metadata = create_test_metadata("h1", "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata("h1".to_string(), metadata, "{}".to_string());
+}
+
+#[test]
+fn test_unrevealed_private_intent_is_swept() {
+    // 2000-01-01T00:00:00Z
+    set_context(accounts(1), 946_684_800_000_000_000);
+    let mut contract = Contract::new(accounts(1));
+    contract.store_private_intent("h1".to_string(), accounts(1).to_string(), "2000-01-01T00:00:05Z".to_string());
+
+    set_context(accounts(4), 946_684_810_000_000_000);
+    assert_eq!(contract.sweep_expired_intents(None), 1);
+    assert!(contract.get_private_intent("h1".to_string()).is_none());
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Expired));
+}
+
+#[test]
+fn test_conformance_corpus_matches_vector_check() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let corpus = contract.generate_conformance_corpus(42, 500);
+    assert_eq!(corpus.len(), 50);
+    assert!(corpus.iter().any(|case| case.error.is_some()));
+    for case in corpus.into_iter().filter(|case| case.intent_hash.is_some()) {
+        let result = contract.verify_intent_hash(case.input_json, case.intent_hash.unwrap());
+        assert!(result.matches, "case {}", case.case_id);
+    }
+}
+
+#[test]
+fn test_intent_webhook_requires_target_consent() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let signer = accounts(3);
+    set_context(signer.clone(), 1_000_000_000);
+    contract.store_private_intent("h1".to_string(), signer.to_string(), "2024-12-31T23:59:59Z".to_string());
+
+    set_context(accounts(4), 1_000_000_000);
+    contract.consent_to_webhooks(signer.clone());
+    set_context(signer, 1_000_000_000);
+    contract.register_intent_webhook("h1".to_string(), accounts(4), "on_fill".to_string(), 10);
+
+    let target = contract.get_intent_webhook("h1".to_string()).unwrap();
+    assert_eq!(target.contract_id, accounts(4).to_string());
+    assert_eq!(target.gas_tgas, 10);
+}
+
+#[test]
+#[should_panic(expected = "Webhook target has not consented")]
+fn test_intent_webhook_rejects_without_consent() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    set_context(accounts(3), 1_000_000_000);
+    contract.store_private_intent("h1".to_string(), accounts(3).to_string(), "2024-12-31T23:59:59Z".to_string());
+
+    contract.register_intent_webhook("h1".to_string(), accounts(4), "on_fill".to_string(), 10);
+}
+
+#[test]
+fn test_jcs_hash_version() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let canonical = Contract::canonical_intent(&intent_json).unwrap();
+    assert_eq!(canonical.jcs, canonical.json);
+
+    let jcs_hash = canonical.hash(HASH_VERSION_JCS);
+    assert_ne!(jcs_hash, canonical.hash(HASH_VERSION_DOMAIN_SEPARATED));
+    let result = contract.verify_intent_hash(intent_json, jcs_hash);
+    assert!(result.matches);
+    assert_eq!(result.hash_version, Some(HASH_VERSION_JCS));
+}
+
+/// Bracket of stored intents "entry" -> ["stop", "take"] signed by accounts(3)
+fn setup_bracket(contract: &mut Contract) {
+    for hash in ["entry", "stop", "take"] {
+        contract
+            .canonical_intents
+            .insert(hash.to_string(), format!("{{\"signer_id\":\"{}\"}}", accounts(3)));
+    }
+    set_context(accounts(3), 1_000_000_000);
+    contract.create_bracket_group("entry".to_string(), vec!["stop".to_string(), "take".to_string()]);
+    set_context(accounts(2), 1_000_000_000);
+}
+
+#[test]
+fn test_bracket_children_wait_for_parent() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    setup_bracket(&mut contract);
+
+    let group = contract.get_bracket_group("take".to_string()).unwrap();
+    assert_eq!(group.parent_hash, "entry");
+    contract.log_execution("entry".to_string(), create_test_execution_log("entry", &accounts(2)), None);
+    contract.log_execution("take".to_string(), create_test_execution_log("take", &accounts(2)), None);
+    assert!(contract.get_execution_log("take".to_string()).is_some());
+    assert_eq!(contract.get_intent_status("stop".to_string()), Some(IntentStatus::Cancelled));
+    assert!(contract.get_topics().contains(&"unknown:unknown:bracket_resolved".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Bracket sibling take already filled; stop is cancelled")]
+fn test_bracket_sibling_fill_cancels_others() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    setup_bracket(&mut contract);
+
+    contract.log_execution("entry".to_string(), create_test_execution_log("entry", &accounts(2)), None);
+    contract.log_execution("take".to_string(), create_test_execution_log("take", &accounts(2)), None);
+    contract.log_execution("stop".to_string(), create_test_execution_log("stop", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Bracket parent entry has not filled")]
+fn test_bracket_child_before_parent_rejected() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    setup_bracket(&mut contract);
+
+    contract.log_execution("stop".to_string(), create_test_execution_log("stop", &accounts(2)), None);
+}
+
+#[test]
+fn test_bracket_fill_cancels_stored_siblings() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    for hash in ["entry", "stop", "take"] {
+        contract.insert_intent_metadata(hash.to_string(), create_test_metadata(hash, "BTC-USD", "1.0", 100));
+    }
+    for child in ["stop", "take"] {
+        contract.bracket_parents.insert(child.to_string(), "entry".to_string());
+    }
+    contract.bracket_groups.insert(
+        "entry".to_string(),
+        BracketGroup {
+            parent_hash: "entry".to_string(),
+            children: vec!["stop".to_string(), "take".to_string()],
+            signer_id: accounts(3).to_string(),
+            created_at: 0,
+        },
+    );
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("entry".to_string(), create_test_execution_log("entry", &accounts(2)), None);
+    contract.log_execution("take".to_string(), create_test_execution_log("take", &accounts(2)), None);
+    assert_eq!(contract.get_intent_status("stop".to_string()), Some(IntentStatus::Cancelled));
+    assert!(contract.get_intent_metadata("stop".to_string()).unwrap().cancelled_at.is_some());
+    assert!(contract.get_topics().contains(&"BTC-USD:perp:bracket_resolved".to_string()));
+}
+
+#[test]
+fn test_intent_tags_counted_and_filterable() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_intent_tag("hedge".to_string(), "taxonomy".to_string());
+    contract.add_intent_tag("arb".to_string(), "taxonomy".to_string());
+
+    let mut hedge = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    hedge.tags = vec!["hedge".to_string()];
+    contract.insert_intent_metadata("h1".to_string(), hedge);
+    contract.insert_intent_metadata("h2".to_string(), create_test_metadata("h2", "BTC-USD", "1.0", 200));
+
+    let tags = contract.get_intent_tags();
+    assert_eq!(tags.iter().map(|t| (t.tag.as_str(), t.intent_count)).collect::<Vec<_>>(), vec![("arb", 0), ("hedge", 1)]);
+    let pending = contract.get_pending_intents(PendingIntentFilter {
+        tag: Some("hedge".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].intent_hash, "h1");
+}
+
+#[test]
+#[should_panic(expected = "Unknown intent tag: spec")]
+fn test_intent_tags_must_be_in_taxonomy() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let mut metadata = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    metadata.tags = vec!["spec".to_string()];
+    contract.insert_intent_metadata("h1".to_string(), metadata);
+}
+
+#[test]
+fn test_constraint_defaults_per_schema_version() {
+    setup_test_context();
+    let contract = Contract::new(accounts(1));
+    let defaults = contract.get_constraint_defaults("1.0.0".to_string()).unwrap();
+    let fields: Vec<&str> = defaults.iter().map(|d| d.field.as_str()).collect();
+    assert_eq!(fields, vec!["max_fee_bps", "max_funding_bps_8h", "max_slippage_bps", "venue_allowlist"]);
+    assert_eq!(defaults[0].default, serde_json::Value::from(30u64));
+    assert_eq!(defaults[2].max, Some(1000));
+    assert!(contract.get_constraint_defaults("2.0.0".to_string()).is_none());
+}
+
+#[test]
+fn test_trace_id_indexed_and_logged() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    for hash in ["h1", "h2"] {
+        let mut metadata = create_test_metadata(hash, "BTC-USD", "1.0", 100);
+        metadata.trace_id = Some("req-42".to_string());
+        contract.insert_intent_metadata(hash.to_string(), metadata);
+    }
+    assert_eq!(contract.get_intents_by_trace_id("req-42".to_string(), None, None), vec!["h1", "h2"]);
+    assert_eq!(contract.get_intents_by_trace_id("req-42".to_string(), Some(1), Some(5)), vec!["h2"]);
+    assert!(contract.get_intents_by_trace_id("other".to_string(), None, None).is_empty());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    assert!(near_sdk::test_utils::get_logs()
+        .iter()
+        .any(|log| log.ends_with("(attempt 1) trace_id=req-42")));
+}
+
+#[test]
+fn test_execution_must_reference_signed_quote() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.register_quote_key("11".repeat(32));
+    contract.signed_quotes.insert(
+        "q1".to_string(),
+        SolverQuote {
+            intent_hash: "h1".to_string(),
+            solver_id: accounts(2).to_string(),
+            price: "50000.0".to_string(),
+            fee_bps: 5,
+            expires_at: 2_000_000_000,
+        },
+    );
+    let mut log = create_test_execution_log("h1", &accounts(2));
+    log.quote_id = Some("q1".to_string());
+    contract.log_execution("h1".to_string(), log, None);
+    assert!(contract.get_execution_log("h1".to_string()).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Execution must reference a registered quote")]
+fn test_execution_without_quote_rejected_once_key_registered() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.register_quote_key("11".repeat(32));
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Fill is outside the quote validity window")]
+fn test_quote_window_uses_block_time() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.register_quote_key("11".repeat(32));
+    contract.signed_quotes.insert(
+        "q1".to_string(),
+        SolverQuote {
+            intent_hash: "h1".to_string(),
+            solver_id: accounts(2).to_string(),
+            price: "50000.0".to_string(),
+            fee_bps: 5,
+            expires_at: 2_000_000_000,
+        },
+    );
+    // A backdated log timestamp does not reopen an expired quote
+    set_context(accounts(2), 3_000_000_000);
+    let mut log = create_test_execution_log("h1", &accounts(2));
+    log.quote_id = Some("q1".to_string());
+    contract.log_execution("h1".to_string(), log, None);
+}
+
+#[test]
+fn test_cancel_intent_hides_it_from_pending() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+    assert!(contract.get_intent_metadata("h1".to_string()).unwrap().cancelled_at.is_some());
+    assert!(contract.get_pending_intents(PendingIntentFilter::default()).is_empty());
+    assert!(contract.get_topics().contains(&"BTC-USD:perp:intent_cancelled".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Intent has been cancelled")]
+fn test_cancelled_intent_cannot_be_executed() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Only the intent signer or its solver can cancel")]
+fn test_cancel_intent_rejects_other_accounts() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(4), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+}
+
+#[test]
+fn test_intent_status_codes() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    set_context(accounts(3), 1_000_000_000);
+    contract.store_private_intent("h2".to_string(), accounts(3).to_string(), "2024-12-31T23:59:59Z".to_string());
+    assert_eq!(contract.get_intent_status_code("h1".to_string()), 2);
+    assert_eq!(contract.get_intent_status_code("h2".to_string()), 1);
+    assert_eq!(contract.get_intent_status_code("none".to_string()), 0);
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    assert_eq!(contract.get_intent_status_code("h1".to_string()), 5);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    assert_eq!(contract.get_intent_status_code("h1".to_string()), 3);
+}
+
+#[test]
+fn test_restoring_metadata_keeps_cancellation() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let mut metadata = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    metadata.cancelled_at = Some(1);
+    contract.insert_intent_metadata("h1".to_string(), metadata);
+    assert!(contract.get_intent_metadata("h1".to_string()).unwrap().cancelled_at.is_none());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    assert!(contract.get_intent_metadata("h1".to_string()).unwrap().cancelled_at.is_some());
+}
+
+#[test]
+fn test_restoring_metadata_keeps_first_solver() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    let mut metadata = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    metadata.solver_id = accounts(5).to_string();
+    contract.insert_intent_metadata("h1".to_string(), metadata);
+    assert_eq!(contract.get_intent_metadata("h1".to_string()).unwrap().solver_id, accounts(2).to_string());
+}
+
+#[test]
+#[should_panic(expected = "Only the intent signer or its solver can cancel")]
+fn test_cancel_intent_requires_authorized_solver() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+}
+
+#[test]
+fn test_parse_deadline_ns() {
+    assert_eq!(parse_deadline_ns("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(parse_deadline_ns("2024-12-31T23:59:59Z"), Some(1_735_689_599_000_000_000));
+    assert_eq!(parse_deadline_ns("2000-02-29T12:00:00.500Z"), Some(951_825_600_000_000_000));
+    assert_eq!(parse_deadline_ns("2024-12-31T23:59:59+01:00"), None);
+    assert_eq!(parse_deadline_ns("2024-13-01T00:00:00Z"), None);
+}
+
+#[test]
+fn test_sweep_expired_intents() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    for (hash, deadline) in [("h1", 5_000_000_000u64), ("h2", 50_000_000_000), ("h3", 5_000_000_000)] {
+        contract.insert_intent_metadata(hash.to_string(), create_test_metadata(hash, "BTC-USD", "1.0", 100));
+        contract.index_deadline(hash, deadline);
+    }
+
+    set_context(accounts(4), 10_000_000_000);
+    assert_eq!(contract.sweep_expired_intents(Some(1)), 1);
+    assert_eq!(contract.sweep_expired_intents(None), 1);
+    assert_eq!(contract.sweep_expired_intents(None), 0);
+
+    assert!(contract.get_intent_metadata("h1".to_string()).is_none());
+    assert_eq!(contract.get_intent_status_code("h3".to_string()), 6);
+    assert_eq!(contract.get_intent_status_code("h2".to_string()), 2);
+    assert_eq!(contract.get_intent_timeline("h1".to_string()).last().unwrap().event, TimelineEvent::Expired);
+    assert!(contract.get_topics().contains(&"BTC-USD:perp:intent_expired".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Intent deadline has passed")]
+fn test_log_execution_rejects_past_deadline() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    contract.index_deadline("h1", 5_000_000_000);
+
+    set_context(accounts(2), 10_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+fn test_admin_actions_record_reasons() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.set_parameter("draft_expiry_ns".to_string(), 3_600_000_000_000, "shorter drafts".to_string());
+    contract.add_reviewer(accounts(3), "new reviewer".to_string());
+    contract.add_reviewer(accounts(3), "duplicate".to_string());
+
+    let actions = contract.get_admin_actions(None, None);
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].action, "set_parameter");
+    assert_eq!(actions[0].target, "draft_expiry_ns=3600000000000");
+    assert_eq!(actions[0].reason, "shorter drafts");
+    assert_eq!(actions[1].action_id, 1);
+    assert_eq!(actions[1].actor, accounts(1).to_string());
+    assert_eq!(contract.get_admin_actions(Some(1), Some(5)).len(), 1);
+    assert!(contract.get_topics().contains(&"unknown:unknown:admin_action".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Treasury actions require a reason")]
+fn test_admin_action_rejects_blank_reason() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_trader(accounts(2), "  ".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Reason must be at most 256 bytes")]
+fn test_admin_action_rejects_long_reason() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_intent_tag("hedge".to_string(), "x".repeat(257));
+}
+
+#[test]
+fn test_amend_intent_links_and_cancels_old_hash() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent = create_test_intent_v2();
+    let intent_json = serde_json::to_string(&intent).unwrap();
+    let old_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let metadata = create_test_metadata(&old_hash, "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata(old_hash.clone(), metadata, intent_json);
+
+    let mut amended = intent;
+    amended.derivatives.size = "500.0".to_string();
+    amended.nonce = "12346".to_string();
+    let new_hash = contract.amend_intent(old_hash.clone(), serde_json::to_string(&amended).unwrap());
+
+    let old = contract.get_intent_metadata(old_hash.clone()).unwrap();
+    assert!(old.cancelled_at.is_some());
+    assert_eq!(old.amended_by, Some(new_hash.clone()));
+    let new = contract.get_intent_metadata(new_hash.clone()).unwrap();
+    assert_eq!(new.amends, Some(old_hash.clone()));
+    assert_eq!(new.size, "500");
+    assert_eq!(contract.get_intent_status_code(old_hash.clone()), 4);
+    assert_eq!(contract.get_intent_status_code(new_hash), 2);
+    assert_eq!(contract.get_intent_timeline(old_hash).last().unwrap().event, TimelineEvent::Amended);
+    assert!(contract.get_topics().contains(&"BTC-USD:perp:intent_amended".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Only the intent signer can amend")]
+fn test_amend_intent_rejects_other_accounts() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    let intent_json = serde_json::to_string(&create_test_intent_v2()).unwrap();
+    let old_hash = Contract::canonical_intent(&intent_json).unwrap().hash(HASH_VERSION_LEGACY);
+    let metadata = create_test_metadata(&old_hash, "BTC-USD", "1000.0", 100);
+    contract.store_intent_metadata(old_hash.clone(), metadata, intent_json.clone());
+
+    set_context(accounts(4), 1_000_000_000);
+    contract.amend_intent(old_hash, intent_json);
+}
+
+#[test]
+fn test_anchor_document_by_solver() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    let doc_hash = "ab".repeat(32);
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.anchor_document(
+        "h1".to_string(),
+        doc_hash.clone(),
+        "confirmation".to_string(),
+        "ipfs://confirmation".to_string(),
+    );
+
+    let documents = contract.get_intent_documents("h1".to_string());
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].anchored_by, accounts(2).to_string());
+    assert!(contract.verify_document("h1".to_string(), doc_hash).is_some());
+    assert!(contract.verify_document("h1".to_string(), "cd".repeat(32)).is_none());
+    assert!(contract.get_topics().contains(&"BTC-USD:perp:document_anchored".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Document already anchored for this intent")]
+fn test_anchor_document_rejects_duplicate() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    for _ in 0..2 {
+        contract.anchor_document("h1".to_string(), "ab".repeat(32), "term_sheet".to_string(), String::new());
+    }
+}
+
+#[test]
+#[should_panic(expected = "doc_hash must be a lowercase hex sha256")]
+fn test_anchor_document_rejects_malformed_hash() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.anchor_document("h1".to_string(), "AB".repeat(32), "term_sheet".to_string(), String::new());
+}
+
+#[test]
+fn test_health_reports_worst_component() {
+    let context_with_balance = |predecessor: AccountId, block_timestamp: u64, balance_near: u128| {
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor)
+            .block_timestamp(block_timestamp)
+            .account_balance(NearToken::from_near(balance_near))
+            .storage_usage(10_000);
+        testing_env!(context.build());
+    };
+    context_with_balance(accounts(1), 1_000_000_000, 100);
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let health = contract.get_health();
+    assert_eq!(health.status, HealthStatus::Green);
+    assert_eq!(health.components.len(), 3);
+
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    contract.index_deadline("h1", 5_000_000_000);
+    context_with_balance(accounts(2), 10_000_000_000, 100);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    // Overdue intents are counted once their deadline hour has ended
+    assert_eq!(contract.get_health().components[1].status, HealthStatus::Green);
+    context_with_balance(accounts(2), EXPIRY_BUCKET_NS + 10_000_000_000, 100);
+
+    let health = contract.get_health();
+    let status_of = |name: &str| health.components.iter().find(|c| c.component == name).unwrap().status;
+    assert_eq!(status_of("dead_letter_queue"), HealthStatus::Yellow);
+    assert_eq!(status_of("expiry_sweep"), HealthStatus::Yellow);
+    assert_eq!(status_of("storage"), HealthStatus::Green);
+    assert_eq!(health.status, HealthStatus::Yellow);
+
+    context_with_balance(accounts(2), EXPIRY_BUCKET_NS + 10_000_000_000, 0);
+    assert_eq!(contract.get_health().status, HealthStatus::Red);
+}
+
+#[test]
+fn test_health_counters_follow_transitions() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    for hash in ["h1", "h2"] {
+        contract.insert_intent_metadata(hash.to_string(), create_test_metadata(hash, "BTC-USD", "1.0", 100));
+        contract.index_deadline(hash, 5_000_000_000);
+    }
+    set_context(accounts(2), 2_000_000_000);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    contract.report_failed_execution("h2".to_string(), "timeout".to_string());
+    assert_eq!(contract.unretried_dead_letters, 2);
+    contract.retry_failed_execution("h1".to_string());
+    assert_eq!(contract.unretried_dead_letters, 1);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    assert_eq!(contract.unretried_dead_letters, 2);
+
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    assert_eq!(contract.unretried_dead_letters, 1);
+    assert_eq!(contract.expiry_bucket_counts.get(&0), Some(&1));
+
+    set_context(accounts(2), EXPIRY_BUCKET_NS + 1);
+    assert!(contract.get_health().components[1].detail.starts_with("1 "));
+    contract.sweep_expired_intents(None);
+    assert_eq!(contract.unretried_dead_letters, 0);
+    assert_eq!(contract.expiry_bucket_counts.get(&0), None);
+    assert_eq!(contract.get_health().status, HealthStatus::Green);
+}
+
+#[test]
+fn test_intent_status_follows_lifecycle() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    assert_eq!(contract.get_intent_status("h1".to_string()), None);
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Submitted));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Failed));
+    contract.retry_failed_execution("h1".to_string());
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Submitted));
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Executed));
+
+    // Re-storing metadata does not reopen an executed intent
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Executed));
+}
+
+#[test]
+#[should_panic(expected = "cannot move from Executed to Failed")]
+fn test_intent_status_rejects_failure_after_execution() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    contract.report_failed_execution("h1".to_string(), "timeout".to_string());
+}
+
+#[test]
+fn test_sweep_keeps_cancelled_status() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    contract.index_deadline("h1", 5_000_000_000);
+    set_context(accounts(2), 2_000_000_000);
+    contract.cancel_intent("h1".to_string());
+
+    set_context(accounts(4), 10_000_000_000);
+    assert_eq!(contract.sweep_expired_intents(None), 1);
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Cancelled));
+}
+
+#[test]
+fn test_sweep_clears_indexes_and_walks_expiry_hours() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_intent_tag("hedge".to_string(), "taxonomy".to_string());
+    for (hash, deadline) in [("h1", 5_000_000_000u64), ("h2", 3 * EXPIRY_BUCKET_NS + 5_000_000_000)] {
+        let mut metadata = create_test_metadata(hash, "BTC-USD", "1.0", 100);
+        metadata.tags = vec!["hedge".to_string()];
+        metadata.trace_id = Some("req-1".to_string());
+        contract.insert_intent_metadata(hash.to_string(), metadata);
+        contract.index_deadline(hash, deadline);
+    }
+    contract.bracket_parents.insert("h2".to_string(), "h1".to_string());
+    contract.bracket_groups.insert(
+        "h1".to_string(),
+        BracketGroup {
+            parent_hash: "h1".to_string(),
+            children: vec!["h2".to_string()],
+            signer_id: accounts(1).to_string(),
+            created_at: 0,
+        },
+    );
+
+    set_context(accounts(4), 10_000_000_000);
+    assert_eq!(contract.sweep_expired_intents(None), 1);
+    assert_eq!(contract.get_intents_by_trace_id("req-1".to_string(), None, None), vec!["h2"]);
+    assert_eq!(contract.get_intent_tags()[0].intent_count, 1);
+    assert!(contract.get_bracket_group("h2".to_string()).is_none());
+    assert_eq!(contract.expiry_cursor, 0);
+
+    set_context(accounts(4), 4 * EXPIRY_BUCKET_NS);
+    assert_eq!(contract.sweep_expired_intents(None), 1);
+    assert_eq!(contract.expiry_cursor, 4);
+    assert!(contract.get_intents_by_trace_id("req-1".to_string(), None, None).is_empty());
+    assert_eq!(contract.get_intent_tags()[0].intent_count, 0);
+}
+
+#[test]
+fn test_trigger_gates_execution_until_met() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_oracle(accounts(4), "mark price feed".to_string());
+    let mut stop = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    stop.trigger = Some(TriggerCondition {
+        trigger_price: "45000.5".to_string(),
+        direction: TriggerDirection::Below,
+        price_source: "mark".to_string(),
+    });
+    contract.insert_intent_metadata("h1".to_string(), stop);
+
+    set_context(accounts(4), 1_000_000_000);
+    assert!(!contract.check_trigger("h1".to_string(), "46000".to_string()));
+    assert!(contract.get_intent_metadata("h1".to_string()).unwrap().triggered_at.is_none());
+    assert!(contract.check_trigger("h1".to_string(), "45000.5".to_string()));
+    assert_eq!(contract.get_intent_timeline("h1".to_string()).last().unwrap().event, TimelineEvent::Triggered);
+
+    set_context(accounts(2), 2_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+    assert_eq!(contract.get_intent_status("h1".to_string()), Some(IntentStatus::Executed));
+}
+
+#[test]
+#[should_panic(expected = "Trigger condition has not been met")]
+fn test_untriggered_intent_cannot_be_executed() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let mut take_profit = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    take_profit.trigger = Some(TriggerCondition {
+        trigger_price: "60000".to_string(),
+        direction: TriggerDirection::Above,
+        price_source: "index".to_string(),
+    });
+    contract.insert_intent_metadata("h1".to_string(), take_profit);
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Trigger condition has not been met")]
+fn test_restoring_metadata_keeps_trigger() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    let mut take_profit = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    take_profit.trigger = Some(TriggerCondition {
+        trigger_price: "60000".to_string(),
+        direction: TriggerDirection::Above,
+        price_source: "index".to_string(),
+    });
+    contract.insert_intent_metadata("h1".to_string(), take_profit);
+    contract.insert_intent_metadata("h1".to_string(), create_test_metadata("h1", "BTC-USD", "1.0", 100));
+    assert!(contract.get_intent_metadata("h1".to_string()).unwrap().trigger.is_some());
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.log_execution("h1".to_string(), create_test_execution_log("h1", &accounts(2)), None);
+}
+
+#[test]
+#[should_panic(expected = "Cancelled or executed intents cannot be triggered")]
+fn test_cancelled_intent_cannot_be_triggered() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.add_authorized_solver(accounts(2), "solver onboarding".to_string());
+    contract.add_oracle(accounts(4), "mark price feed".to_string());
+    let mut stop = create_test_metadata("h1", "BTC-USD", "1.0", 100);
+    stop.trigger = Some(TriggerCondition {
+        trigger_price: "45000".to_string(),
+        direction: TriggerDirection::Below,
+        price_source: "mark".to_string(),
+    });
+    contract.insert_intent_metadata("h1".to_string(), stop);
+
+    set_context(accounts(2), 1_000_000_000);
+    contract.cancel_intent("h1".to_string());
+    set_context(accounts(4), 1_000_000_000);
+    contract.check_trigger("h1".to_string(), "44000".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Only oracles can check triggers")]
+fn test_check_trigger_requires_oracle() {
+    setup_test_context();
+    let mut contract = Contract::new(accounts(1));
+    contract.check_trigger("h1".to_string(), "1".to_string());
+}
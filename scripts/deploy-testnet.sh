@@ -105,12 +105,12 @@ configure_contract() {
     
     # Add authorized solvers
     near call $CONTRACT_NAME add_authorized_solver \
-        "{\"solver_id\": \"$SOLVER1_ACCOUNT\"}" \
-        --accountId $CONTRACT_NAME
+        "{\"solver_id\": \"$SOLVER1_ACCOUNT\", \"reason\": \"testnet solver\"}" \
+        --accountId $TREASURY_ACCOUNT
     
     near call $CONTRACT_NAME add_authorized_solver \
-        "{\"solver_id\": \"$SOLVER2_ACCOUNT\"}" \
-        --accountId $CONTRACT_NAME
+        "{\"solver_id\": \"$SOLVER2_ACCOUNT\", \"reason\": \"testnet solver\"}" \
+        --accountId $TREASURY_ACCOUNT
     
     echo -e "${GREEN}Contract configured${NC}"
 }
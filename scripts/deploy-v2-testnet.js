@@ -64,8 +64,8 @@ async function deployV2Contract() {
   console.log(`   near view ${CONTRACT_ACCOUNT} validate_v2_intent '${JSON.stringify({ intent: testIntent }).replace(/'/g, "\\'")}'`);
   console.log('');
   console.log('5. Add authorized solvers:');
-  console.log(`   near call ${CONTRACT_ACCOUNT} add_authorized_solver '{"solver_id": "solver1.testnet"}' --accountId ${CONTRACT_ACCOUNT}`);
-  console.log(`   near call ${CONTRACT_ACCOUNT} add_authorized_solver '{"solver_id": "solver2.testnet"}' --accountId ${CONTRACT_ACCOUNT}`);
+  console.log(`   near call ${CONTRACT_ACCOUNT} add_authorized_solver '{"solver_id": "solver1.testnet", "reason": "testnet solver"}' --accountId ${TREASURY_ACCOUNT}`);
+  console.log(`   near call ${CONTRACT_ACCOUNT} add_authorized_solver '{"solver_id": "solver2.testnet", "reason": "testnet solver"}' --accountId ${TREASURY_ACCOUNT}`);
   console.log('');
   console.log('Contract Features Available:');
   console.log('- ✓ V2 Schema support (Collateral + Constraints)');
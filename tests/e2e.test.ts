@@ -35,11 +35,13 @@ describe('End-to-End Tests', () => {
       args: { treasury_account_id: treasury.accountId },
     });
 
-    await contract.call(contract, 'add_authorized_solver', {
+    await treasury.call(contract, 'add_authorized_solver', {
       solver_id: 'solver1.testnet',
+      reason: 'e2e solver',
     });
-    await contract.call(contract, 'add_authorized_solver', {
+    await treasury.call(contract, 'add_authorized_solver', {
       solver_id: 'solver2.testnet',
+      reason: 'e2e solver',
     });
 
     gatewayProcess = spawn('node', ['dist/index.js'], {